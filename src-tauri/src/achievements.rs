@@ -0,0 +1,281 @@
+use crate::database::{self, AppSettings, DbPool};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, State};
+
+/// Event emitted once per achievement, when it unlocks.
+pub const UNLOCKED_EVENT: &str = "achievement-unlocked";
+
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+    /// Completed work sessions, all time
+    TotalPomodoros(u32),
+    /// Longest run of days meeting the daily goal
+    Streak(u32),
+    /// Focused minutes in a single day
+    DeepWorkDay(u32),
+    TasksCompleted(u32),
+}
+
+struct Definition {
+    id: &'static str,
+    title: &'static str,
+    description: &'static str,
+    /// File name under `static/icons`
+    icon: &'static str,
+    rule: Rule,
+}
+
+const DEFINITIONS: &[Definition] = &[
+    Definition {
+        id: "first_pomodoro",
+        title: "First tomato",
+        description: "Complete your first pomodoro",
+        icon: "tomato.svg",
+        rule: Rule::TotalPomodoros(1),
+    },
+    Definition {
+        id: "pomodoros_100",
+        title: "Centurion",
+        description: "Complete 100 pomodoros",
+        icon: "goal.svg",
+        rule: Rule::TotalPomodoros(100),
+    },
+    Definition {
+        id: "pomodoros_1000",
+        title: "Forest",
+        description: "Complete 1,000 pomodoros",
+        icon: "forest.svg",
+        rule: Rule::TotalPomodoros(1000),
+    },
+    Definition {
+        id: "streak_7",
+        title: "On fire",
+        description: "Meet your daily goal 7 days in a row",
+        icon: "fire.svg",
+        rule: Rule::Streak(7),
+    },
+    Definition {
+        id: "streak_30",
+        title: "Full bloom",
+        description: "Meet your daily goal 30 days in a row",
+        icon: "sakura.svg",
+        rule: Rule::Streak(30),
+    },
+    Definition {
+        id: "deep_work_day",
+        title: "Deep work",
+        description: "Focus for 4 hours in a single day",
+        icon: "headphone.svg",
+        rule: Rule::DeepWorkDay(240),
+    },
+    Definition {
+        id: "tasks_50",
+        title: "Getting things done",
+        description: "Complete 50 tasks",
+        icon: "tick.svg",
+        rule: Rule::TasksCompleted(50),
+    },
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Achievement {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// File name under `static/icons`
+    pub icon: String,
+    pub unlocked_at: Option<String>,
+    /// Current value toward `target`, capped at `target`
+    pub progress: u32,
+    pub target: u32,
+    pub percent: f64,
+}
+
+/// The values the rules are checked against.
+struct Metrics {
+    total_pomodoros: u32,
+    longest_streak: u32,
+    best_day_minutes: u32,
+    tasks_completed: u32,
+}
+
+fn metrics(conn: &rusqlite::Connection, settings: &AppSettings) -> Result<Metrics, String> {
+    let days = crate::streaks::focus_by_day(conn, None, None)?;
+    let (_, longest_streak) =
+        crate::streaks::compute_streaks(&days, settings, crate::schedule::today());
+
+    let tasks_completed = conn
+        .query_row(
+            "SELECT COUNT(*) FROM tasks WHERE completed = 1",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(Metrics {
+        total_pomodoros: days.values().map(|(pomodoros, _)| pomodoros).sum(),
+        longest_streak,
+        best_day_minutes: days
+            .values()
+            .map(|(_, minutes)| *minutes)
+            .max()
+            .unwrap_or(0),
+        tasks_completed,
+    })
+}
+
+fn progress(rule: Rule, metrics: &Metrics) -> (u32, u32) {
+    match rule {
+        Rule::TotalPomodoros(target) => (metrics.total_pomodoros, target),
+        Rule::Streak(target) => (metrics.longest_streak, target),
+        Rule::DeepWorkDay(target) => (metrics.best_day_minutes, target),
+        Rule::TasksCompleted(target) => (metrics.tasks_completed, target),
+    }
+}
+
+/// Share of `target` reached, as a percentage with one decimal.
+fn percent(progress: u32, target: u32) -> f64 {
+    (progress as f64 / target.max(1) as f64 * 1000.0).round() / 10.0
+}
+
+fn unlocked(conn: &rusqlite::Connection) -> Result<HashMap<String, String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, unlocked_at FROM achievements")
+        .map_err(|e| format!("Database error: {}", e))?;
+    let unlocked = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(unlocked)
+}
+
+/// Check every rule, record newly met ones and return all achievements along
+/// with the ids that unlocked during this call.
+pub fn evaluate(
+    conn: &rusqlite::Connection,
+    settings: &AppSettings,
+) -> Result<(Vec<Achievement>, Vec<String>), String> {
+    let metrics = metrics(conn, settings)?;
+    let mut unlocked = unlocked(conn)?;
+    let mut newly_unlocked = Vec::new();
+
+    let mut achievements = Vec::with_capacity(DEFINITIONS.len());
+    for definition in DEFINITIONS {
+        let (value, target) = progress(definition.rule, &metrics);
+
+        if value >= target && !unlocked.contains_key(definition.id) {
+            let inserted = conn
+                .execute(
+                    "INSERT OR IGNORE INTO achievements (id, unlocked_at) VALUES (?1, ?2)",
+                    params![definition.id, chrono::Utc::now().to_rfc3339()],
+                )
+                .map_err(|e| format!("Database error: {}", e))?;
+            // Another caller may have unlocked it since `unlocked` was read; only
+            // the one whose insert landed reports it
+            if inserted == 1 {
+                newly_unlocked.push(definition.id.to_string());
+            }
+            let unlocked_at = conn
+                .query_row(
+                    "SELECT unlocked_at FROM achievements WHERE id = ?1",
+                    params![definition.id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| format!("Database error: {}", e))?;
+            unlocked.insert(definition.id.to_string(), unlocked_at);
+        }
+
+        let progress = value.min(target);
+        achievements.push(Achievement {
+            id: definition.id.to_string(),
+            title: definition.title.to_string(),
+            description: definition.description.to_string(),
+            icon: definition.icon.to_string(),
+            unlocked_at: unlocked.get(definition.id).cloned(),
+            progress,
+            target,
+            percent: percent(progress, target),
+        });
+    }
+
+    Ok((achievements, newly_unlocked))
+}
+
+fn emit_unlocked(
+    app: &AppHandle,
+    achievements: &[Achievement],
+    newly_unlocked: &[String],
+) -> Result<(), String> {
+    for achievement in achievements
+        .iter()
+        .filter(|achievement| newly_unlocked.contains(&achievement.id))
+    {
+        app.emit(UNLOCKED_EVENT, achievement)
+            .map_err(|e| format!("Failed to emit achievement: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Unlock whatever the latest activity earned and tell the frontend about it.
+/// Runs after each completed work session; achievements earned some other way,
+/// such as completing tasks, unlock then or when the list is next loaded.
+pub fn check_achievements(app: &AppHandle, conn: &rusqlite::Connection) -> Result<(), String> {
+    let settings = database::load_settings(app)?;
+    let (achievements, newly_unlocked) = evaluate(conn, &settings)?;
+    emit_unlocked(app, &achievements, &newly_unlocked)
+}
+
+/// All achievements, unlocked ones with their unlock time and locked ones with
+/// progress toward their target.
+#[tauri::command]
+pub async fn get_achievements(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+) -> Result<Vec<Achievement>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let settings = database::load_settings(&app)?;
+    let (achievements, newly_unlocked) = evaluate(&conn, &settings)?;
+    emit_unlocked(&app, &achievements, &newly_unlocked)?;
+
+    Ok(achievements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics() -> Metrics {
+        Metrics {
+            total_pomodoros: 150,
+            longest_streak: 9,
+            best_day_minutes: 180,
+            tasks_completed: 0,
+        }
+    }
+
+    #[test]
+    fn progress_reads_the_rule_metric() {
+        let metrics = metrics();
+        assert_eq!(progress(Rule::TotalPomodoros(100), &metrics), (150, 100));
+        assert_eq!(progress(Rule::Streak(30), &metrics), (9, 30));
+        assert_eq!(progress(Rule::DeepWorkDay(240), &metrics), (180, 240));
+        assert_eq!(progress(Rule::TasksCompleted(50), &metrics), (0, 50));
+    }
+
+    #[test]
+    fn percent_rounds_to_one_decimal() {
+        assert_eq!(percent(0, 50), 0.0);
+        assert_eq!(percent(1, 3), 33.3);
+        assert_eq!(percent(2, 3), 66.7);
+        assert_eq!(percent(9, 30), 30.0);
+        assert_eq!(percent(1, 1000), 0.1);
+        assert_eq!(percent(30, 30), 100.0);
+        assert_eq!(percent(0, 0), 0.0);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::database::{self, AppSettings, DbPool};
+use chrono::{DateTime, Utc};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+/// Event emitted when a completed work session makes the current chain too long.
+pub const BREAK_WARNING_EVENT: &str = "break-warning";
+
+/// Minutes a break may run past its planned length before it counts as overrun.
+const OVERRUN_GRACE_MINUTES: i64 = 2;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChainWarning {
+    /// Local `YYYY-MM-DD`
+    pub date: String,
+    /// Start of the first work session in the chain
+    pub started_at: String,
+    /// Completed work sessions in the chain
+    pub sessions: u32,
+    pub focus_minutes: u32,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BreakStats {
+    /// Completed work sessions followed by another session the same day
+    pub breaks_due: u32,
+    /// Completed short and long breaks
+    pub breaks_taken: u32,
+    pub short_breaks: u32,
+    pub long_breaks: u32,
+    /// Breaks that were started but ended early or interrupted
+    pub skipped_breaks: u32,
+    /// Breaks that lasted longer than planned, measured to the next session
+    pub overrun_breaks: u32,
+    pub overrun_minutes: u32,
+    /// Work sessions started right after a completed work session
+    pub work_without_break: u32,
+    /// `breaks_taken / breaks_due` as a percentage, `None` when nothing was due
+    pub adherence_rate: Option<f64>,
+    /// Most completed work sessions in a row without a real rest
+    pub longest_chain: u32,
+    /// Chains longer than `max_chained_work_sessions`
+    pub warnings: Vec<ChainWarning>,
+}
+
+struct TimelineSession {
+    session_type: String,
+    duration_minutes: u32,
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+    day: String,
+    completed: bool,
+}
+
+impl TimelineSession {
+    fn is_work(&self) -> bool {
+        self.session_type == "work"
+    }
+}
+
+/// Completed work sessions since the last real rest.
+#[derive(Default)]
+struct Chain {
+    day: String,
+    sessions: u32,
+    focus_minutes: u32,
+    started_at: Option<DateTime<Utc>>,
+}
+
+fn load_timeline(
+    conn: &rusqlite::Connection,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Vec<TimelineSession>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT session_type, duration_minutes, started_at, completed_at,
+                    DATE(started_at, 'localtime'), completed_at IS NOT NULL AND interrupted = 0
+             FROM pomodoro_sessions
+             WHERE (completed_at IS NOT NULL OR interrupted = 1)
+               AND (?1 IS NULL OR DATE(started_at, 'localtime') >= ?1)
+               AND (?2 IS NULL OR DATE(started_at, 'localtime') <= ?2)
+             ORDER BY started_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![from, to], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, u32>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, bool>(5)?,
+            ))
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let parse = |value: &str| {
+        DateTime::parse_from_rfc3339(value)
+            .map(|time| time.with_timezone(&Utc))
+            .ok()
+    };
+
+    let mut sessions = Vec::new();
+    for row in rows {
+        let (session_type, duration_minutes, started_at, completed_at, day, completed) =
+            row.map_err(|e| format!("Database error: {}", e))?;
+        let Some(started_at) = parse(&started_at) else {
+            continue;
+        };
+        // Interrupted sessions have no end time; assume they ran their planned length
+        let ended_at = completed_at
+            .as_deref()
+            .and_then(parse)
+            .unwrap_or(started_at + chrono::Duration::minutes(duration_minutes as i64));
+
+        sessions.push(TimelineSession {
+            session_type,
+            duration_minutes,
+            started_at,
+            ended_at,
+            day,
+            completed,
+        });
+    }
+
+    Ok(sessions)
+}
+
+fn close_chain(chain: &mut Chain, settings: &AppSettings, stats: &mut BreakStats) {
+    if chain.sessions > settings.max_chained_work_sessions {
+        stats.warnings.push(chain_warning(chain));
+    }
+    *chain = Chain::default();
+}
+
+fn chain_warning(chain: &Chain) -> ChainWarning {
+    ChainWarning {
+        date: chain.day.clone(),
+        started_at: chain
+            .started_at
+            .map(|started_at| started_at.to_rfc3339())
+            .unwrap_or_default(),
+        sessions: chain.sessions,
+        focus_minutes: chain.focus_minutes,
+        message: format!(
+            "{} work sessions ({} minutes) in a row without a real break",
+            chain.sessions, chain.focus_minutes
+        ),
+    }
+}
+
+/// Walk the sessions in order. A completed break, a long enough idle gap or the
+/// end of the day counts as rest and ends the current chain of work sessions.
+/// Returns the stats and the chain still open after the last session.
+fn analyze(sessions: &[TimelineSession], settings: &AppSettings) -> (BreakStats, Chain) {
+    let mut stats = BreakStats::default();
+    let mut chain = Chain::default();
+    let rest_gap = chrono::Duration::minutes(settings.long_break_duration.max(1) as i64);
+
+    for (index, session) in sessions.iter().enumerate() {
+        let previous = index
+            .checked_sub(1)
+            .and_then(|index| sessions.get(index))
+            .filter(|previous| previous.day == session.day);
+        let next = sessions
+            .get(index + 1)
+            .filter(|next| next.day == session.day);
+
+        let rested = match previous {
+            None => true,
+            Some(previous) => session.started_at - previous.ended_at >= rest_gap,
+        };
+        if rested {
+            close_chain(&mut chain, settings, &mut stats);
+        }
+
+        if session.is_work() {
+            if let Some(previous) = previous {
+                if !rested && previous.is_work() && previous.completed {
+                    stats.work_without_break += 1;
+                }
+            }
+            if session.completed {
+                if next.is_some() {
+                    stats.breaks_due += 1;
+                }
+                if chain.sessions == 0 {
+                    chain.day = session.day.clone();
+                    chain.started_at = Some(session.started_at);
+                }
+                chain.sessions += 1;
+                chain.focus_minutes += session.duration_minutes;
+                stats.longest_chain = stats.longest_chain.max(chain.sessions);
+            }
+        } else if session.completed {
+            stats.breaks_taken += 1;
+            if session.session_type == "long_break" {
+                stats.long_breaks += 1;
+            } else {
+                stats.short_breaks += 1;
+            }
+            if let Some(next) = next {
+                let taken = (next.started_at - session.started_at).num_minutes();
+                let overrun = taken - session.duration_minutes as i64;
+                if overrun > OVERRUN_GRACE_MINUTES {
+                    stats.overrun_breaks += 1;
+                    stats.overrun_minutes += overrun as u32;
+                }
+            }
+            close_chain(&mut chain, settings, &mut stats);
+        } else {
+            stats.skipped_breaks += 1;
+        }
+    }
+
+    let open_chain = std::mem::take(&mut chain);
+    if open_chain.sessions > settings.max_chained_work_sessions {
+        stats.warnings.push(chain_warning(&open_chain));
+    }
+
+    stats.adherence_rate = (stats.breaks_due > 0).then(|| {
+        let rate = stats.breaks_taken.min(stats.breaks_due) as f64 / stats.breaks_due as f64;
+        (rate * 1000.0).round() / 10.0
+    });
+
+    (stats, open_chain)
+}
+
+/// Emit `BREAK_WARNING_EVENT` when today's open chain of work sessions has just
+/// gone past the configured limit. Later sessions in the same chain don't
+/// repeat the warning.
+pub fn notify_chain_warning(app: &AppHandle, conn: &rusqlite::Connection) -> Result<(), String> {
+    let settings = database::load_settings(app)?;
+    let today = crate::schedule::today().format("%Y-%m-%d").to_string();
+    let sessions = load_timeline(conn, Some(&today), Some(&today))?;
+
+    let (_, open_chain) = analyze(&sessions, &settings);
+    if open_chain.sessions == settings.max_chained_work_sessions.saturating_add(1) {
+        app.emit(BREAK_WARNING_EVENT, chain_warning(&open_chain))
+            .map_err(|e| format!("Failed to emit break warning: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Break adherence between the inclusive local dates `from` and `to`.
+#[tauri::command]
+pub async fn get_break_stats(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<BreakStats, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let from = from
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?
+        .map(|date| date.format("%Y-%m-%d").to_string());
+    let to = to
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?
+        .map(|date| date.format("%Y-%m-%d").to_string());
+
+    let settings = database::load_settings(&app)?;
+    let sessions = load_timeline(&conn, from.as_deref(), to.as_deref())?;
+
+    Ok(analyze(&sessions, &settings).0)
+}
@@ -0,0 +1,235 @@
+use crate::database::{DbPool, Task};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+const ITEM_COLUMNS: &str = "id, task_id, text, completed, completed_at, position,
+    (SELECT COUNT(*) FROM pomodoro_sessions s
+     WHERE s.checklist_item_id = task_checklist_items.id
+       AND s.session_type = 'work' AND s.interrupted = 0 AND s.completed_at IS NOT NULL)";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChecklistItem {
+    pub id: String,
+    pub task_id: String,
+    pub text: String,
+    pub completed: bool,
+    pub completed_at: Option<String>,
+    pub position: i32,
+    /// Completed work sessions attributed to this item
+    pub actual_pomodoros: u32,
+}
+
+/// How far along a task's checklist is, e.g. 3 of 7 items done.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ChecklistProgress {
+    pub completed: u32,
+    pub total: u32,
+}
+
+impl ChecklistProgress {
+    fn from_items(items: &[ChecklistItem]) -> Self {
+        Self {
+            completed: items.iter().filter(|item| item.completed).count() as u32,
+            total: items.len() as u32,
+        }
+    }
+}
+
+fn item_from_row(row: &rusqlite::Row) -> rusqlite::Result<ChecklistItem> {
+    Ok(ChecklistItem {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        text: row.get(2)?,
+        completed: row.get::<_, i32>(3)? != 0,
+        completed_at: row.get(4)?,
+        position: row.get(5)?,
+        actual_pomodoros: row.get(6)?,
+    })
+}
+
+fn get_item(conn: &rusqlite::Connection, item_id: &str) -> Result<ChecklistItem, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM task_checklist_items WHERE id = ?1", ITEM_COLUMNS),
+        params![item_id],
+        item_from_row,
+    )
+    .map_err(|e| format!("Database error: {}", e))
+}
+
+fn task_items(conn: &rusqlite::Connection, task_id: &str) -> Result<Vec<ChecklistItem>, String> {
+    let mut stmt = conn
+        .prepare_cached(&format!(
+            "SELECT {} FROM task_checklist_items WHERE task_id = ?1 ORDER BY position, created_at",
+            ITEM_COLUMNS
+        ))
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let items = stmt
+        .query_map(params![task_id], item_from_row)
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(items)
+}
+
+/// Check that a checklist item exists and belongs to `task_id`, so sessions can
+/// be attributed to it.
+pub fn ensure_task_item(
+    conn: &rusqlite::Connection,
+    task_id: &str,
+    item_id: &str,
+) -> Result<(), String> {
+    conn.query_row(
+        "SELECT 1 FROM task_checklist_items WHERE id = ?1 AND task_id = ?2",
+        params![item_id, task_id],
+        |_| Ok(()),
+    )
+    .optional()
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or_else(|| {
+        format!(
+            "Checklist item {} does not belong to task {}",
+            item_id, task_id
+        )
+    })
+}
+
+/// Fill in `Task::checklist` and `Task::checklist_progress` for loaded tasks.
+pub fn attach_checklists(conn: &rusqlite::Connection, tasks: &mut [Task]) -> Result<(), String> {
+    for task in tasks.iter_mut() {
+        task.checklist = task_items(conn, &task.id)?;
+        task.checklist_progress = ChecklistProgress::from_items(&task.checklist);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn add_checklist_item(
+    state: State<'_, DbPool>,
+    task_id: String,
+    text: String,
+) -> Result<ChecklistItem, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("Checklist item text cannot be empty".to_string());
+    }
+
+    conn.query_row(
+        "SELECT 1 FROM tasks WHERE id = ?1",
+        params![task_id],
+        |_| Ok(()),
+    )
+    .optional()
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO task_checklist_items (id, task_id, text, completed, position, created_at)
+         VALUES (?1, ?2, ?3, 0,
+                 (SELECT COALESCE(MAX(position), -1) + 1 FROM task_checklist_items WHERE task_id = ?2),
+                 ?4)",
+        params![id, task_id, text, chrono::Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    get_item(&conn, &id)
+}
+
+#[tauri::command]
+pub async fn update_checklist_item(
+    state: State<'_, DbPool>,
+    item_id: String,
+    text: String,
+) -> Result<ChecklistItem, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("Checklist item text cannot be empty".to_string());
+    }
+
+    conn.execute(
+        "UPDATE task_checklist_items SET text = ?1 WHERE id = ?2",
+        params![text, item_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    get_item(&conn, &item_id)
+}
+
+#[tauri::command]
+pub async fn complete_checklist_item(
+    state: State<'_, DbPool>,
+    item_id: String,
+    completed: bool,
+) -> Result<ChecklistItem, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let completed_at = if completed {
+        Some(chrono::Utc::now().to_rfc3339())
+    } else {
+        None
+    };
+
+    conn.execute(
+        "UPDATE task_checklist_items SET completed = ?1, completed_at = ?2 WHERE id = ?3",
+        params![completed, completed_at, item_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    get_item(&conn, &item_id)
+}
+
+#[tauri::command]
+pub async fn delete_checklist_item(state: State<'_, DbPool>, item_id: String) -> Result<(), String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    conn.execute(
+        "UPDATE pomodoro_sessions SET checklist_item_id = NULL WHERE checklist_item_id = ?1",
+        params![item_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    conn.execute(
+        "DELETE FROM task_checklist_items WHERE id = ?1",
+        params![item_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(())
+}
+
+/// Reorder a task's checklist to match `item_ids`. Items left out keep their
+/// relative order after the listed ones.
+#[tauri::command]
+pub async fn reorder_checklist_items(
+    state: State<'_, DbPool>,
+    task_id: String,
+    item_ids: Vec<String>,
+) -> Result<Vec<ChecklistItem>, String> {
+    let pool = state.inner();
+    let mut conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let current = task_items(&conn, &task_id)?
+        .into_iter()
+        .map(|item| item.id)
+        .collect();
+    crate::database::write_order(
+        &mut conn,
+        "UPDATE task_checklist_items SET position = ?1 WHERE id = ?2 AND task_id = ?3",
+        &task_id,
+        item_ids,
+        current,
+    )?;
+
+    task_items(&conn, &task_id)
+}
@@ -1,12 +1,24 @@
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::params;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager, State};
 
+use crate::checklist::{ChecklistItem, ChecklistProgress};
+use crate::streaks::{DailyGoal, GoalProgress};
+
 pub type DbPool = Pool<SqliteConnectionManager>;
 
-const DB_VERSION: i32 = 2;
+const DB_VERSION: i32 = 17;
+
+pub const TASK_COLUMNS: &str = "id, text, completed, created_at, completed_at,
+    COALESCE(priority, 0), COALESCE(estimated_pomodoros, 1), COALESCE(actual_pomodoros, 0),
+    due_date, scheduled_for, recurrence_rule, series_id, notes, archived_at, project";
+
+pub const SESSION_COLUMNS: &str = "id, task_id, session_type, duration_minutes, started_at,
+    completed_at, interrupted, checklist_item_id, reflection, paused_seconds, extended_minutes,
+    (SELECT COUNT(*) FROM interruptions WHERE interruptions.session_id = pomodoro_sessions.id)";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Task {
@@ -18,6 +30,29 @@ pub struct Task {
     pub priority: i32,
     pub estimated_pomodoros: i32,
     pub actual_pomodoros: i32,
+    /// Deadline as a local `YYYY-MM-DD` date
+    pub due_date: Option<String>,
+    /// Day the task is planned to be worked on, as a local `YYYY-MM-DD` date
+    pub scheduled_for: Option<String>,
+    /// Not completed and past its due date
+    pub overdue: bool,
+    /// RRULE subset (see `recurrence::RecurrenceRule`) for repeating tasks
+    pub recurrence_rule: Option<String>,
+    /// Id of the first task in a recurrence series, shared by all of its instances
+    pub series_id: Option<String>,
+    /// Free-form Markdown notes
+    pub notes: Option<String>,
+    /// Set when a completed task is moved out of the default task list
+    pub archived_at: Option<String>,
+    /// Project the task belongs to, set with `@project` in quick-add
+    pub project: Option<String>,
+    /// Waiting on at least one unfinished task in `blocked_by`
+    pub blocked: bool,
+    /// Ids of the unfinished tasks this one depends on
+    pub blocked_by: Vec<String>,
+    pub tags: Vec<String>,
+    pub checklist: Vec<ChecklistItem>,
+    pub checklist_progress: ChecklistProgress,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -29,14 +64,39 @@ pub struct PomodoroSession {
     pub started_at: String,
     pub completed_at: Option<String>,
     pub interrupted: bool,
+    pub tags: Vec<String>,
+    pub checklist_item_id: Option<String>,
+    /// Check-in written after the session: what got done, what got in the way
+    pub reflection: Option<String>,
+    /// Total time the session spent paused
+    pub paused_seconds: u32,
+    /// Minutes added while the session was running, included in `duration_minutes`
+    pub extended_minutes: u32,
+    /// Interruptions logged during the session
+    pub interruptions: u32,
+    /// 0–100, see `focus_score::focus_score`; `None` for breaks and running sessions
+    pub focus_score: Option<u8>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DailyStats {
+    /// Local `YYYY-MM-DD`
     pub date: String,
     pub pomodoros_completed: u32,
     pub total_work_time: u32,
     pub tasks_completed: u32,
+    /// Progress toward the daily goal on this date, counted over all sessions
+    #[serde(default)]
+    pub goal_progress: Option<GoalProgress>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StartedSession {
+    pub session_id: String,
+    /// Set when a work session was started on a task that is still blocked
+    pub warning: Option<String>,
+    /// Ids of the unfinished tasks blocking the session's task
+    pub blocked_by: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -44,13 +104,8 @@ pub struct TaskWithStats {
     pub task: Task,
     pub pomodoro_sessions: Vec<PomodoroSession>,
     pub total_time_spent: u32,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct HeatmapPoint {
-    pub date: String,
-    pub count: u32,
-    pub level: u8,
+    /// Set when more sessions are available than were returned
+    pub next_sessions_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -61,6 +116,36 @@ pub struct AppSettings {
     pub long_break_duration: u32,
     pub sessions_until_long_break: u32,
     pub sound_enabled: bool,
+    /// Completed tasks older than this many days are archived at startup; `None` (the
+    /// default) leaves them in the task list
+    #[serde(default)]
+    pub archive_completed_after_days: Option<u32>,
+    /// Start of the working day as local `HH:MM`, used for daily plan capacity
+    #[serde(default = "default_work_day_start")]
+    pub work_day_start: String,
+    /// End of the working day as local `HH:MM`
+    #[serde(default = "default_work_day_end")]
+    pub work_day_end: String,
+    #[serde(default)]
+    pub daily_goal: DailyGoal,
+    /// Weekdays on which missing the daily goal doesn't break a streak
+    #[serde(default = "crate::streaks::default_rest_days")]
+    pub rest_days: Vec<chrono::Weekday>,
+    /// Completed work sessions in a row without a real break before warning
+    #[serde(default = "default_max_chained_work_sessions")]
+    pub max_chained_work_sessions: u32,
+}
+
+fn default_work_day_start() -> String {
+    "09:00".to_string()
+}
+
+fn default_work_day_end() -> String {
+    "17:00".to_string()
+}
+
+fn default_max_chained_work_sessions() -> u32 {
+    3
 }
 
 impl Default for AppSettings {
@@ -72,10 +157,36 @@ impl Default for AppSettings {
             long_break_duration: 15,
             sessions_until_long_break: 4,
             sound_enabled: true,
+            archive_completed_after_days: None,
+            work_day_start: default_work_day_start(),
+            work_day_end: default_work_day_end(),
+            daily_goal: DailyGoal::default(),
+            rest_days: crate::streaks::default_rest_days(),
+            max_chained_work_sessions: default_max_chained_work_sessions(),
         }
     }
 }
 
+/// Read `settings.json` from the app data directory, falling back to defaults.
+pub fn load_settings(app: &AppHandle) -> Result<AppSettings, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let settings_path = app_data_dir.join("settings.json");
+
+    if settings_path.exists() {
+        let settings_content = std::fs::read_to_string(settings_path)
+            .map_err(|e| format!("Failed to read settings: {}", e))?;
+
+        serde_json::from_str(&settings_content)
+            .map_err(|e| format!("Failed to parse settings: {}", e))
+    } else {
+        Ok(AppSettings::default())
+    }
+}
+
 pub fn initialize_database(app_handle: &AppHandle) -> Result<DbPool, String> {
     let app_data_dir = app_handle
         .path()
@@ -182,6 +293,237 @@ fn migrate_database(conn: &rusqlite::Connection) -> Result<(), String> {
                     )
                     .map_err(|e| format!("Failed to create settings table: {}", e))?;
                 }
+                3 => {
+                    conn.execute(
+                        "CREATE TABLE IF NOT EXISTS tags (
+                            id TEXT PRIMARY KEY,
+                            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                            created_at TEXT NOT NULL
+                        )",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create tags table: {}", e))?;
+
+                    conn.execute(
+                        "CREATE TABLE IF NOT EXISTS task_tags (
+                            task_id TEXT NOT NULL,
+                            tag_id TEXT NOT NULL,
+                            PRIMARY KEY(task_id, tag_id),
+                            FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+                            FOREIGN KEY(tag_id) REFERENCES tags(id) ON DELETE CASCADE
+                        )",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create task_tags table: {}", e))?;
+
+                    conn.execute(
+                        "CREATE TABLE IF NOT EXISTS session_tags (
+                            session_id TEXT NOT NULL,
+                            tag_id TEXT NOT NULL,
+                            PRIMARY KEY(session_id, tag_id),
+                            FOREIGN KEY(session_id) REFERENCES pomodoro_sessions(id) ON DELETE CASCADE,
+                            FOREIGN KEY(tag_id) REFERENCES tags(id) ON DELETE CASCADE
+                        )",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create session_tags table: {}", e))?;
+                }
+                4 => {
+                    conn.execute(
+                        "CREATE TABLE IF NOT EXISTS task_checklist_items (
+                            id TEXT PRIMARY KEY,
+                            task_id TEXT NOT NULL,
+                            text TEXT NOT NULL,
+                            completed BOOLEAN NOT NULL DEFAULT 0,
+                            completed_at TEXT,
+                            position INTEGER NOT NULL DEFAULT 0,
+                            created_at TEXT NOT NULL,
+                            FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+                        )",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create task_checklist_items table: {}", e))?;
+
+                    conn.execute(
+                        "ALTER TABLE pomodoro_sessions ADD COLUMN checklist_item_id TEXT",
+                        [],
+                    )
+                    .ok();
+                }
+                5 => {
+                    conn.execute("ALTER TABLE tasks ADD COLUMN due_date TEXT", [])
+                        .ok();
+
+                    conn.execute("ALTER TABLE tasks ADD COLUMN scheduled_for TEXT", [])
+                        .ok();
+                }
+                6 => {
+                    conn.execute("ALTER TABLE tasks ADD COLUMN recurrence_rule TEXT", [])
+                        .ok();
+
+                    conn.execute("ALTER TABLE tasks ADD COLUMN series_id TEXT", [])
+                        .ok();
+
+                    conn.execute(
+                        "CREATE INDEX IF NOT EXISTS idx_tasks_series_id ON tasks(series_id)",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create series index: {}", e))?;
+                }
+                7 => {
+                    conn.execute("ALTER TABLE tasks ADD COLUMN notes TEXT", [])
+                        .ok();
+
+                    conn.execute(
+                        "ALTER TABLE pomodoro_sessions ADD COLUMN reflection TEXT",
+                        [],
+                    )
+                    .ok();
+                }
+                8 => {
+                    crate::search::create_search_index(conn)?;
+                }
+                9 => {
+                    conn.execute("ALTER TABLE tasks ADD COLUMN archived_at TEXT", [])
+                        .ok();
+
+                    conn.execute(
+                        "CREATE INDEX IF NOT EXISTS idx_tasks_archived_at ON tasks(archived_at)",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create archive index: {}", e))?;
+                }
+                10 => {
+                    conn.execute(
+                        "CREATE TABLE IF NOT EXISTS task_dependencies (
+                            task_id TEXT NOT NULL,
+                            depends_on_id TEXT NOT NULL,
+                            created_at TEXT NOT NULL,
+                            PRIMARY KEY (task_id, depends_on_id)
+                        )",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create task_dependencies table: {}", e))?;
+
+                    conn.execute(
+                        "CREATE INDEX IF NOT EXISTS idx_task_dependencies_depends_on
+                         ON task_dependencies(depends_on_id)",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create dependency index: {}", e))?;
+                }
+                11 => {
+                    conn.execute("ALTER TABLE tasks ADD COLUMN project TEXT", [])
+                        .ok();
+
+                    conn.execute(
+                        "CREATE INDEX IF NOT EXISTS idx_tasks_project ON tasks(project)",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create project index: {}", e))?;
+                }
+                12 => {
+                    conn.execute(
+                        "CREATE TABLE IF NOT EXISTS daily_plan (
+                            date TEXT NOT NULL,
+                            task_id TEXT NOT NULL,
+                            position INTEGER NOT NULL DEFAULT 0,
+                            added_at TEXT NOT NULL,
+                            PRIMARY KEY (date, task_id)
+                        )",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create daily_plan table: {}", e))?;
+                }
+                13 => {
+                    conn.execute(
+                        "CREATE TABLE IF NOT EXISTS interruptions (
+                            id TEXT PRIMARY KEY,
+                            session_id TEXT NOT NULL,
+                            kind TEXT NOT NULL,
+                            occurred_at TEXT NOT NULL,
+                            note TEXT
+                        )",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create interruptions table: {}", e))?;
+
+                    conn.execute(
+                        "CREATE INDEX IF NOT EXISTS idx_interruptions_session_id
+                         ON interruptions(session_id)",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create interruptions index: {}", e))?;
+                }
+                14 => {
+                    conn.execute(
+                        "CREATE TABLE IF NOT EXISTS inbox_items (
+                            id TEXT PRIMARY KEY,
+                            text TEXT NOT NULL,
+                            session_id TEXT,
+                            captured_at TEXT NOT NULL,
+                            status TEXT NOT NULL DEFAULT 'open'
+                                CHECK(status IN ('open', 'converted', 'dismissed')),
+                            task_id TEXT,
+                            resolved_at TEXT
+                        )",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create inbox_items table: {}", e))?;
+                }
+                15 => {
+                    conn.execute(
+                        "ALTER TABLE pomodoro_sessions ADD COLUMN paused_seconds INTEGER NOT NULL DEFAULT 0",
+                        [],
+                    )
+                    .ok();
+                    conn.execute(
+                        "ALTER TABLE pomodoro_sessions ADD COLUMN extended_minutes INTEGER NOT NULL DEFAULT 0",
+                        [],
+                    )
+                    .ok();
+                    conn.execute(
+                        "ALTER TABLE pomodoro_sessions ADD COLUMN paused_at TEXT",
+                        [],
+                    )
+                    .ok();
+                }
+                16 => {
+                    conn.execute(
+                        "CREATE TABLE IF NOT EXISTS achievements (
+                            id TEXT PRIMARY KEY,
+                            unlocked_at TEXT NOT NULL
+                        )",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create achievements table: {}", e))?;
+                }
+                17 => {
+                    conn.execute(
+                        "CREATE TABLE IF NOT EXISTS garden_plants (
+                            id TEXT PRIMARY KEY,
+                            session_id TEXT NOT NULL UNIQUE,
+                            task_id TEXT,
+                            species TEXT NOT NULL,
+                            stage TEXT NOT NULL
+                                CHECK(stage IN ('sprout', 'growing', 'blooming', 'withered')),
+                            focus_score INTEGER,
+                            planted_at TEXT NOT NULL,
+                            date TEXT NOT NULL
+                        )",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create garden_plants table: {}", e))?;
+
+                    conn.execute(
+                        "CREATE INDEX IF NOT EXISTS idx_garden_plants_date ON garden_plants(date)",
+                        [],
+                    )
+                    .map_err(|e| format!("Failed to create garden index: {}", e))?;
+
+                    // Sessions from before the garden still get their plants
+                    crate::garden::plant_all(conn)?;
+                }
                 _ => {}
             }
         }
@@ -196,6 +538,142 @@ fn migrate_database(conn: &rusqlite::Connection) -> Result<(), String> {
     Ok(())
 }
 
+pub fn task_from_row(row: &rusqlite::Row) -> rusqlite::Result<Task> {
+    let completed = row.get::<_, i32>(2)? != 0;
+    let due_date: Option<String> = row.get(8)?;
+
+    Ok(Task {
+        id: row.get(0)?,
+        text: row.get(1)?,
+        completed,
+        created_at: row.get(3)?,
+        completed_at: row.get(4)?,
+        priority: row.get::<_, Option<i32>>(5)?.unwrap_or(0),
+        estimated_pomodoros: row.get::<_, Option<i32>>(6)?.unwrap_or(1),
+        actual_pomodoros: row.get::<_, Option<i32>>(7)?.unwrap_or(0),
+        overdue: !completed && crate::schedule::is_overdue(due_date.as_deref()),
+        due_date,
+        scheduled_for: row.get(9)?,
+        recurrence_rule: row.get(10)?,
+        series_id: row.get(11)?,
+        notes: row.get(12)?,
+        archived_at: row.get(13)?,
+        project: row.get(14)?,
+        blocked: false,
+        blocked_by: Vec::new(),
+        tags: Vec::new(),
+        checklist: Vec::new(),
+        checklist_progress: ChecklistProgress::default(),
+    })
+}
+
+pub fn session_from_row(row: &rusqlite::Row) -> rusqlite::Result<PomodoroSession> {
+    Ok(PomodoroSession {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        session_type: row.get(2)?,
+        duration_minutes: row.get(3)?,
+        started_at: row.get(4)?,
+        completed_at: row.get(5)?,
+        interrupted: row.get::<_, i32>(6)? != 0,
+        tags: Vec::new(),
+        checklist_item_id: row.get(7)?,
+        reflection: row.get(8)?,
+        paused_seconds: row.get(9)?,
+        extended_minutes: row.get(10)?,
+        interruptions: row.get(11)?,
+        focus_score: None,
+    })
+    .map(|mut session| {
+        session.focus_score = crate::focus_score::focus_score(&session);
+        session
+    })
+}
+
+/// Load a single task with its related rows.
+pub fn load_task(conn: &rusqlite::Connection, task_id: &str) -> Result<Task, String> {
+    let mut task = conn
+        .query_row(
+            &format!("SELECT {} FROM tasks WHERE id = ?1", TASK_COLUMNS),
+            params![task_id],
+            task_from_row,
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Task not found: {}", task_id),
+            e => format!("Database error: {}", e),
+        })?;
+    attach_task_details(conn, std::slice::from_mut(&mut task))?;
+
+    Ok(task)
+}
+
+/// Load tasks matching an SQL `condition` over the `tasks` table, with related rows.
+pub fn load_tasks_where<P: rusqlite::Params>(
+    conn: &rusqlite::Connection,
+    condition: &str,
+    order_by: &str,
+    params: P,
+) -> Result<Vec<Task>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM tasks WHERE {} ORDER BY {}",
+            TASK_COLUMNS, condition, order_by
+        ))
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let task_iter = stmt
+        .query_map(params, task_from_row)
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut tasks = Vec::new();
+    for task in task_iter {
+        tasks.push(task.map_err(|e| format!("Database error: {}", e))?);
+    }
+
+    attach_task_details(conn, &mut tasks)?;
+
+    Ok(tasks)
+}
+
+/// Load the related rows (tags, checklist, dependencies) that are not part of the `tasks` table.
+pub fn attach_task_details(conn: &rusqlite::Connection, tasks: &mut [Task]) -> Result<(), String> {
+    crate::tags::attach_task_tags(conn, tasks)?;
+    crate::checklist::attach_checklists(conn, tasks)?;
+    crate::dependencies::attach_blocked(conn, tasks)?;
+    Ok(())
+}
+
+/// Renumber the `position`s of a list so the ids in `requested` come first, in
+/// that order, and the rest of `current` keep their relative order after them.
+/// `update_sql` binds the position as `?1`, the row id as `?2` and `scope` (the
+/// date or task the list belongs to) as `?3`.
+pub fn write_order(
+    conn: &mut rusqlite::Connection,
+    update_sql: &str,
+    scope: &str,
+    requested: Vec<String>,
+    current: Vec<String>,
+) -> Result<(), String> {
+    let mut ordered: Vec<String> = Vec::new();
+    for id in requested.into_iter().chain(current) {
+        if !ordered.contains(&id) {
+            ordered.push(id);
+        }
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    for (position, id) in ordered.iter().enumerate() {
+        tx.execute(update_sql, params![position as i32, id, scope])
+            .map_err(|e| format!("Database error: {}", e))?;
+    }
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
 #[tauri::command]
 pub async fn add_task(state: State<'_, DbPool>, text: String) -> Result<Task, String> {
     let pool = state.inner();
@@ -210,6 +688,19 @@ pub async fn add_task(state: State<'_, DbPool>, text: String) -> Result<Task, St
         priority: 0,
         estimated_pomodoros: 1,
         actual_pomodoros: 0,
+        due_date: None,
+        scheduled_for: None,
+        overdue: false,
+        recurrence_rule: None,
+        series_id: None,
+        notes: None,
+        archived_at: None,
+        project: None,
+        blocked: false,
+        blocked_by: Vec::new(),
+        tags: Vec::new(),
+        checklist: Vec::new(),
+        checklist_progress: ChecklistProgress::default(),
     };
 
     conn.execute(
@@ -231,39 +722,23 @@ pub async fn add_task(state: State<'_, DbPool>, text: String) -> Result<Task, St
 }
 
 #[tauri::command]
-pub async fn get_tasks(state: State<'_, DbPool>) -> Result<Vec<Task>, String> {
+pub async fn get_tasks(
+    state: State<'_, DbPool>,
+    tags: Option<Vec<String>>,
+) -> Result<Vec<Task>, String> {
     let pool = state.inner();
     let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
 
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, text, completed, created_at, completed_at, 
-                    COALESCE(priority, 0), COALESCE(estimated_pomodoros, 1), COALESCE(actual_pomodoros, 0)
-             FROM tasks ORDER BY priority DESC, created_at DESC",
-        )
-        .map_err(|e| format!("Database error: {}", e))?;
-
-    let task_iter = stmt
-        .query_map([], |row| {
-            Ok(Task {
-                id: row.get(0)?,
-                text: row.get(1)?,
-                completed: row.get::<_, i32>(2)? != 0,
-                created_at: row.get(3)?,
-                completed_at: row.get(4)?,
-                priority: row.get::<_, Option<i32>>(5)?.unwrap_or(0),
-                estimated_pomodoros: row.get::<_, Option<i32>>(6)?.unwrap_or(1),
-                actual_pomodoros: row.get::<_, Option<i32>>(7)?.unwrap_or(0),
-            })
-        })
-        .map_err(|e| format!("Database error: {}", e))?;
-
-    let mut tasks = Vec::new();
-    for task in task_iter {
-        tasks.push(task.map_err(|e| format!("Database error: {}", e))?);
-    }
-
-    Ok(tasks)
+    let tags = crate::tags::normalize_tag_filter(tags);
+    load_tasks_where(
+        &conn,
+        &format!(
+            "archived_at IS NULL AND {}",
+            crate::tags::task_tag_condition("tasks.id", 1, tags.len())
+        ),
+        "priority DESC, created_at DESC",
+        params_from_iter(tags.iter()),
+    )
 }
 
 #[tauri::command]
@@ -271,7 +746,7 @@ pub async fn complete_task(
     state: State<'_, DbPool>,
     task_id: String,
     completed: bool,
-) -> Result<(), String> {
+) -> Result<Option<Task>, String> {
     let pool = state.inner();
     let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
 
@@ -281,43 +756,175 @@ pub async fn complete_task(
         None
     };
 
+    // Un-completing a task brings it back out of the archive
     conn.execute(
-        "UPDATE tasks SET completed = ?1, completed_at = ?2 WHERE id = ?3",
+        "UPDATE tasks SET completed = ?1, completed_at = ?2,
+                          archived_at = CASE WHEN ?1 THEN archived_at END
+         WHERE id = ?3",
         params![completed, completed_at, task_id],
     )
     .map_err(|e| format!("Database error: {}", e))?;
 
     if completed {
-        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let today = crate::schedule::today().format("%Y-%m-%d").to_string();
         conn.execute(
             "INSERT OR REPLACE INTO daily_stats (date, tasks_completed, created_at) 
              VALUES (?1, COALESCE((SELECT tasks_completed FROM daily_stats WHERE date = ?1), 0) + 1, ?2)",
             params![&today, &chrono::Utc::now().to_rfc3339()],
         )
         .map_err(|e| format!("Database error: {}", e))?;
+
+        // Completing an instance of a recurring task queues up the next one
+        let task = load_task(&conn, &task_id)?;
+        return crate::recurrence::spawn_next_instance(&conn, &task);
     }
 
-    Ok(())
+    Ok(None)
+}
+
+/// A partial update to a task; fields left as `None` keep their current value.
+#[derive(Debug, Default, Clone)]
+pub struct TaskPatch {
+    pub text: Option<String>,
+    pub priority: Option<i32>,
+    pub estimated_pomodoros: Option<i32>,
+    /// `Some("")` clears the notes
+    pub notes: Option<String>,
+    /// `Some("")` clears the project
+    pub project: Option<String>,
+}
+
+/// Validate and apply a `TaskPatch`, returning the updated task.
+pub fn patch_task(
+    conn: &rusqlite::Connection,
+    task_id: &str,
+    patch: TaskPatch,
+) -> Result<Task, String> {
+    let text = match patch.text {
+        Some(text) if text.trim().is_empty() => return Err("Task text cannot be empty".to_string()),
+        text => text.map(|text| text.trim().to_string()),
+    };
+    if let Some(priority) = patch.priority {
+        if !(0..=3).contains(&priority) {
+            return Err(format!(
+                "Priority must be between 0 and 3, got {}",
+                priority
+            ));
+        }
+    }
+    if let Some(estimated_pomodoros) = patch.estimated_pomodoros {
+        if estimated_pomodoros < 1 {
+            return Err("Estimated pomodoros must be at least 1".to_string());
+        }
+    }
+
+    let updated = conn
+        .execute(
+            "UPDATE tasks SET text = COALESCE(?1, text),
+                              priority = COALESCE(?2, priority),
+                              estimated_pomodoros = COALESCE(?3, estimated_pomodoros),
+                              notes = CASE WHEN ?4 IS NULL THEN notes ELSE NULLIF(?4, '') END,
+                              project = CASE WHEN ?5 IS NULL THEN project ELSE NULLIF(TRIM(?5), '') END
+             WHERE id = ?6",
+            params![
+                text,
+                patch.priority,
+                patch.estimated_pomodoros,
+                patch.notes,
+                patch.project,
+                task_id
+            ],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if updated == 0 {
+        return Err(format!("Task not found: {}", task_id));
+    }
+
+    load_task(conn, task_id)
 }
 
 #[tauri::command]
 pub async fn update_task(
     state: State<'_, DbPool>,
     task_id: String,
-    text: String,
+    text: Option<String>,
+    priority: Option<i32>,
+    estimated_pomodoros: Option<i32>,
+    notes: Option<String>,
+    project: Option<String>,
+) -> Result<Task, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    patch_task(
+        &conn,
+        &task_id,
+        TaskPatch {
+            text,
+            priority,
+            estimated_pomodoros,
+            notes,
+            project,
+        },
+    )
+}
+
+#[tauri::command]
+pub async fn update_task_priority(
+    state: State<'_, DbPool>,
+    task_id: String,
     priority: i32,
+) -> Result<Task, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    patch_task(
+        &conn,
+        &task_id,
+        TaskPatch {
+            priority: Some(priority),
+            ..Default::default()
+        },
+    )
+}
+
+#[tauri::command]
+pub async fn update_task_estimate(
+    state: State<'_, DbPool>,
+    task_id: String,
     estimated_pomodoros: i32,
-) -> Result<(), String> {
+) -> Result<Task, String> {
     let pool = state.inner();
     let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
 
-    conn.execute(
-        "UPDATE tasks SET text = ?1, priority = ?2, estimated_pomodoros = ?3 WHERE id = ?4",
-        params![text, priority, estimated_pomodoros, task_id],
+    patch_task(
+        &conn,
+        &task_id,
+        TaskPatch {
+            estimated_pomodoros: Some(estimated_pomodoros),
+            ..Default::default()
+        },
     )
-    .map_err(|e| format!("Database error: {}", e))?;
+}
 
-    Ok(())
+#[tauri::command]
+pub async fn update_task_notes(
+    state: State<'_, DbPool>,
+    task_id: String,
+    notes: String,
+) -> Result<Task, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    patch_task(
+        &conn,
+        &task_id,
+        TaskPatch {
+            notes: Some(notes),
+            ..Default::default()
+        },
+    )
 }
 
 #[tauri::command]
@@ -325,6 +932,23 @@ pub async fn delete_task(state: State<'_, DbPool>, task_id: String) -> Result<()
     let pool = state.inner();
     let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
 
+    conn.execute("DELETE FROM task_tags WHERE task_id = ?1", params![task_id])
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    conn.execute(
+        "DELETE FROM task_checklist_items WHERE task_id = ?1",
+        params![task_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    crate::dependencies::delete_task_dependencies(&conn, &task_id)?;
+
+    conn.execute(
+        "DELETE FROM daily_plan WHERE task_id = ?1",
+        params![task_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
     conn.execute("DELETE FROM tasks WHERE id = ?1", params![task_id])
         .map_err(|e| format!("Database error: {}", e))?;
 
@@ -337,33 +961,77 @@ pub async fn start_pomodoro_session(
     task_id: Option<String>,
     session_type: String,
     duration_minutes: u32,
-) -> Result<String, String> {
+    tags: Option<Vec<String>>,
+    checklist_item_id: Option<String>,
+) -> Result<StartedSession, String> {
     let pool = state.inner();
     let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
 
+    if let Some(item_id) = checklist_item_id.as_deref() {
+        let task_id = task_id
+            .as_deref()
+            .ok_or("A session on a checklist item needs its task")?;
+        crate::checklist::ensure_task_item(&conn, task_id, item_id)?;
+    }
+
     let session_id = uuid::Uuid::new_v4().to_string();
     let started_at = chrono::Utc::now().to_rfc3339();
 
     conn.execute(
-        "INSERT INTO pomodoro_sessions (id, task_id, session_type, duration_minutes, started_at, interrupted) 
-         VALUES (?1, ?2, ?3, ?4, ?5, 0)",
-        params![session_id, task_id, session_type, duration_minutes, started_at],
+        "INSERT INTO pomodoro_sessions (id, task_id, session_type, duration_minutes, started_at, interrupted, checklist_item_id) 
+         VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6)",
+        params![session_id, task_id, session_type, duration_minutes, started_at, checklist_item_id],
     )
     .map_err(|e| format!("Database error: {}", e))?;
 
-    Ok(session_id)
+    for tag in tags.unwrap_or_default() {
+        let tag_id = crate::tags::ensure_tag(&conn, &tag)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO session_tags (session_id, tag_id) VALUES (?1, ?2)",
+            params![session_id, tag_id],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    }
+
+    // Starting is still allowed; the UI decides how loudly to surface the warning
+    let blocked_by = match task_id.as_deref() {
+        Some(task_id) if session_type == "work" => {
+            crate::dependencies::open_blockers(&conn, task_id)?
+        }
+        _ => Vec::new(),
+    };
+    let warning = if blocked_by.is_empty() {
+        None
+    } else {
+        Some(format!(
+            "This task is blocked by {} unfinished task{}",
+            blocked_by.len(),
+            if blocked_by.len() == 1 { "" } else { "s" }
+        ))
+    };
+
+    Ok(StartedSession {
+        session_id,
+        warning,
+        blocked_by,
+    })
 }
 
 #[tauri::command]
 pub async fn complete_pomodoro_session(
+    app: AppHandle,
     state: State<'_, DbPool>,
     session_id: String,
     was_completed: bool,
     was_interrupted: bool,
+    reflection: Option<String>,
 ) -> Result<(), String> {
     let pool = state.inner();
     let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
 
+    // A session ended while paused stops counting pause time at the end
+    crate::focus_score::close_pause(&conn, &session_id)?;
+
     let completed_at = if was_completed {
         Some(chrono::Utc::now().to_rfc3339())
     } else {
@@ -371,20 +1039,22 @@ pub async fn complete_pomodoro_session(
     };
 
     conn.execute(
-        "UPDATE pomodoro_sessions SET completed_at = ?1, interrupted = ?2 WHERE id = ?3",
-        params![completed_at, was_interrupted, session_id],
+        "UPDATE pomodoro_sessions SET completed_at = ?1, interrupted = ?2,
+                                      reflection = COALESCE(NULLIF(TRIM(?3), ''), reflection)
+         WHERE id = ?4",
+        params![completed_at, was_interrupted, reflection, session_id],
     )
     .map_err(|e| format!("Database error: {}", e))?;
 
-    let session_info: (String, Option<String>) = conn
+    let session_info: (String, Option<String>, u32) = conn
         .query_row(
-            "SELECT session_type, task_id FROM pomodoro_sessions WHERE id = ?1",
+            "SELECT session_type, task_id, duration_minutes FROM pomodoro_sessions WHERE id = ?1",
             params![session_id],
-            |row| Ok((row.get(0)?, row.get(1)?)),
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
         )
         .map_err(|e| format!("Database error: {}", e))?;
 
-    let (session_type, task_id) = session_info;
+    let (session_type, task_id, duration_minutes) = session_info;
 
     if session_type == "work" && was_completed && !was_interrupted {
         if let Some(tid) = task_id {
@@ -395,7 +1065,7 @@ pub async fn complete_pomodoro_session(
             .map_err(|e| format!("Database error: {}", e))?;
         }
 
-        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let today = crate::schedule::today().format("%Y-%m-%d").to_string();
         conn.execute(
             "INSERT INTO daily_stats (date, pomodoros_completed, total_work_time, created_at)
              VALUES (?1, 1, ?2, ?3)
@@ -405,100 +1075,202 @@ pub async fn complete_pomodoro_session(
             params![&today, 25, &chrono::Utc::now().to_rfc3339()],
         )
         .map_err(|e| format!("Database error: {}", e))?;
+
+        if let Err(e) = crate::streaks::notify_goal_reached(&app, &conn, duration_minutes) {
+            eprintln!("{}", e);
+        }
+        if let Err(e) = crate::breaks::notify_chain_warning(&app, &conn) {
+            eprintln!("{}", e);
+        }
+        if let Err(e) = crate::achievements::check_achievements(&app, &conn) {
+            eprintln!("{}", e);
+        }
+
+        // The break starts now: a good moment to triage thoughts parked during focus
+        if let Err(e) = crate::inbox::prompt_review(&app, &conn) {
+            eprintln!("{}", e);
+        }
+    }
+
+    // Completed work grows a plant, interrupted work withers one
+    if session_type == "work" && (was_completed || was_interrupted) {
+        if let Err(e) = crate::garden::plant_session(&conn, &session_id) {
+            eprintln!("{}", e);
+        }
     }
 
     Ok(())
 }
 
-#[tauri::command]
-pub async fn get_task_with_stats(
-    state: State<'_, DbPool>,
-    task_id: String,
+/// Attach a task's sessions, newest first, and its total focused time.
+///
+/// With `sessions_limit` only one page of sessions is returned; pass the returned
+/// `next_sessions_cursor` back as `sessions_cursor` for the next page.
+pub fn task_with_stats(
+    conn: &rusqlite::Connection,
+    task: Task,
+    sessions_limit: Option<u32>,
+    sessions_cursor: Option<&str>,
 ) -> Result<TaskWithStats, String> {
-    let pool = state.inner();
-    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
-
-    let task: Task = conn
+    let total_time_spent: u32 = conn
         .query_row(
-            "SELECT id, text, completed, created_at, completed_at, 
-                    COALESCE(priority, 0), COALESCE(estimated_pomodoros, 1), COALESCE(actual_pomodoros, 0)
-             FROM tasks WHERE id = ?1",
-            params![task_id],
-            |row| {
-                Ok(Task {
-                    id: row.get(0)?,
-                    text: row.get(1)?,
-                    completed: row.get::<_, i32>(2)? != 0,
-                    created_at: row.get(3)?,
-                    completed_at: row.get(4)?,
-                    priority: row.get::<_, Option<i32>>(5)?.unwrap_or(0),
-                    estimated_pomodoros: row.get::<_, Option<i32>>(6)?.unwrap_or(1),
-                    actual_pomodoros: row.get::<_, Option<i32>>(7)?.unwrap_or(0),
-                })
-            },
+            "SELECT COALESCE(SUM(duration_minutes), 0) FROM pomodoro_sessions
+             WHERE task_id = ?1 AND session_type = 'work' AND interrupted = 0",
+            params![task.id],
+            |row| row.get(0),
         )
         .map_err(|e| format!("Database error: {}", e))?;
 
+    let (cursor_started_at, cursor_id) = match sessions_cursor {
+        Some(cursor) => {
+            let mut keys = crate::task_query::decode_cursor(cursor)?.into_iter();
+            match (keys.next(), keys.next()) {
+                (Some(Value::Text(started_at)), Some(Value::Text(id))) => {
+                    (Some(started_at), Some(id))
+                }
+                _ => return Err("Invalid sessions cursor".to_string()),
+            }
+        }
+        None => (None, None),
+    };
+
     let mut stmt = conn
-        .prepare(
-            "SELECT id, task_id, session_type, duration_minutes, started_at, completed_at, interrupted
-             FROM pomodoro_sessions WHERE task_id = ?1 ORDER BY started_at DESC",
-        )
+        .prepare_cached(&format!(
+            "SELECT {} FROM pomodoro_sessions
+             WHERE task_id = ?1 AND (?2 IS NULL OR (started_at, id) < (?2, ?3))
+             ORDER BY started_at DESC, id DESC
+             LIMIT ?4",
+            SESSION_COLUMNS
+        ))
         .map_err(|e| format!("Database error: {}", e))?;
 
+    // Fetch one extra row to find out whether another page follows
+    let fetch_limit = sessions_limit.map(|limit| limit as i64 + 1).unwrap_or(-1);
     let session_iter = stmt
-        .query_map(params![task_id], |row| {
-            Ok(PomodoroSession {
-                id: row.get(0)?,
-                task_id: row.get(1)?,
-                session_type: row.get(2)?,
-                duration_minutes: row.get(3)?,
-                started_at: row.get(4)?,
-                completed_at: row.get(5)?,
-                interrupted: row.get::<_, i32>(6)? != 0,
-            })
-        })
+        .query_map(
+            params![task.id, cursor_started_at, cursor_id, fetch_limit],
+            session_from_row,
+        )
         .map_err(|e| format!("Database error: {}", e))?;
 
     let mut pomodoro_sessions = Vec::new();
-    let mut total_time_spent = 0u32;
-
     for session in session_iter {
-        let session = session.map_err(|e| format!("Database error: {}", e))?;
-        if session.session_type == "work" && !session.interrupted {
-            total_time_spent += session.duration_minutes;
+        pomodoro_sessions.push(session.map_err(|e| format!("Database error: {}", e))?);
+    }
+
+    let mut next_sessions_cursor = None;
+    if let Some(limit) = sessions_limit {
+        if pomodoro_sessions.len() > limit as usize {
+            pomodoro_sessions.truncate(limit as usize);
+            next_sessions_cursor = pomodoro_sessions.last().map(|last| {
+                crate::task_query::encode_cursor(&[
+                    Value::Text(last.started_at.clone()),
+                    Value::Text(last.id.clone()),
+                ])
+            });
         }
-        pomodoro_sessions.push(session);
     }
+    crate::tags::attach_session_tags(conn, &mut pomodoro_sessions)?;
 
     Ok(TaskWithStats {
         task,
         pomodoro_sessions,
         total_time_spent,
+        next_sessions_cursor,
     })
 }
 
+/// Save the check-in for a finished session; an empty string clears it.
 #[tauri::command]
-pub async fn get_daily_stats(state: State<'_, DbPool>) -> Result<Vec<DailyStats>, String> {
+pub async fn set_session_reflection(
+    state: State<'_, DbPool>,
+    session_id: String,
+    reflection: String,
+) -> Result<(), String> {
     let pool = state.inner();
     let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
 
-    let mut stmt = conn
-        .prepare(
-            "SELECT date, pomodoros_completed, total_work_time, tasks_completed 
-             FROM daily_stats ORDER BY date DESC LIMIT 30",
+    let updated = conn
+        .execute(
+            "UPDATE pomodoro_sessions SET reflection = NULLIF(TRIM(?1), '') WHERE id = ?2",
+            params![reflection, session_id],
         )
         .map_err(|e| format!("Database error: {}", e))?;
 
+    if updated == 0 {
+        return Err(format!("Session not found: {}", session_id));
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_task_with_stats(
+    state: State<'_, DbPool>,
+    task_id: String,
+    sessions_limit: Option<u32>,
+    sessions_cursor: Option<String>,
+) -> Result<TaskWithStats, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let task = load_task(&conn, &task_id)?;
+    task_with_stats(&conn, task, sessions_limit, sessions_cursor.as_deref())
+}
+
+fn daily_stats_from_row(row: &rusqlite::Row) -> rusqlite::Result<DailyStats> {
+    Ok(DailyStats {
+        date: row.get(0)?,
+        pomodoros_completed: row.get(1)?,
+        total_work_time: row.get(2)?,
+        tasks_completed: row.get(3)?,
+        goal_progress: None,
+    })
+}
+
+/// Rebuilds daily stats from the raw sessions and tasks carrying every tag in `tags`.
+///
+/// The `daily_stats` table only keeps untagged totals, so tag-filtered views are
+/// aggregated on the fly. Tag names are bound from `?2` onwards; `?1` is the
+/// optional date restriction.
+fn tagged_daily_stats(
+    conn: &rusqlite::Connection,
+    tags: &[String],
+    date: Option<&str>,
+) -> Result<Vec<DailyStats>, String> {
+    let sql = format!(
+        "SELECT date, SUM(pomodoros), SUM(work_time), SUM(tasks_done) FROM (
+            SELECT DATE(s.started_at, 'localtime') AS date, 1 AS pomodoros,
+                   s.duration_minutes AS work_time, 0 AS tasks_done
+            FROM pomodoro_sessions s
+            WHERE s.session_type = 'work'
+              AND s.interrupted = 0
+              AND s.completed_at IS NOT NULL
+              AND {}
+            UNION ALL
+            SELECT DATE(tasks.completed_at, 'localtime'), 0, 0, 1
+            FROM tasks
+            WHERE tasks.completed = 1
+              AND tasks.completed_at IS NOT NULL
+              AND {}
+         )
+         WHERE ?1 IS NULL OR date = ?1
+         GROUP BY date
+         ORDER BY date DESC
+         LIMIT 30",
+        crate::tags::session_tag_condition("s", 2, tags.len()),
+        crate::tags::task_tag_condition("tasks.id", 2, tags.len()),
+    );
+
+    let mut values = vec![date.map(str::to_string)];
+    values.extend(tags.iter().cloned().map(Some));
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Database error: {}", e))?;
+
     let stats_iter = stmt
-        .query_map([], |row| {
-            Ok(DailyStats {
-                date: row.get(0)?,
-                pomodoros_completed: row.get(1)?,
-                total_work_time: row.get(2)?,
-                tasks_completed: row.get(3)?,
-            })
-        })
+        .query_map(params_from_iter(values.iter()), daily_stats_from_row)
         .map_err(|e| format!("Database error: {}", e))?;
 
     let mut stats = Vec::new();
@@ -510,96 +1282,96 @@ pub async fn get_daily_stats(state: State<'_, DbPool>) -> Result<Vec<DailyStats>
 }
 
 #[tauri::command]
-pub async fn get_daily_stats_by_date(
+pub async fn get_daily_stats(
+    app: AppHandle,
     state: State<'_, DbPool>,
-    date: String,
-) -> Result<DailyStats, String> {
+    tags: Option<Vec<String>>,
+) -> Result<Vec<DailyStats>, String> {
     let pool = state.inner();
     let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
 
+    let tags = crate::tags::normalize_tag_filter(tags);
+    let mut stats = if tags.is_empty() {
+        untagged_daily_stats(&conn)?
+    } else {
+        tagged_daily_stats(&conn, &tags, None)?
+    };
+
+    let settings = load_settings(&app)?;
+    crate::streaks::attach_goal_progress(&conn, &settings, &mut stats)?;
+
+    Ok(stats)
+}
+
+fn untagged_daily_stats(conn: &rusqlite::Connection) -> Result<Vec<DailyStats>, String> {
     let mut stmt = conn
         .prepare(
             "SELECT date, pomodoros_completed, total_work_time, tasks_completed 
-             FROM daily_stats WHERE date = ?1",
+             FROM daily_stats ORDER BY date DESC LIMIT 30",
         )
         .map_err(|e| format!("Database error: {}", e))?;
 
-    let result = stmt.query_row([&date], |row| {
-        Ok(DailyStats {
-            date: row.get(0)?,
-            pomodoros_completed: row.get(1)?,
-            total_work_time: row.get(2)?,
-            tasks_completed: row.get(3)?,
-        })
-    });
-
-    match result {
-        Ok(stats) => Ok(stats),
-        Err(rusqlite::Error::QueryReturnedNoRows) => {
-            // No stats for this date, return empty stats
-            Ok(DailyStats {
-                date,
-                pomodoros_completed: 0,
-                total_work_time: 0,
-                tasks_completed: 0,
-            })
-        }
-        Err(e) => Err(format!("Database error: {}", e)),
+    let stats_iter = stmt
+        .query_map([], daily_stats_from_row)
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut stats = Vec::new();
+    for stat in stats_iter {
+        stats.push(stat.map_err(|e| format!("Database error: {}", e))?);
     }
+
+    Ok(stats)
 }
 
 #[tauri::command]
-pub async fn get_focus_heatmap(
+pub async fn get_daily_stats_by_date(
+    app: AppHandle,
     state: State<'_, DbPool>,
-    days: Option<u32>,
-) -> Result<Vec<HeatmapPoint>, String> {
+    date: String,
+    tags: Option<Vec<String>>,
+) -> Result<DailyStats, String> {
     let pool = state.inner();
     let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
 
-    let days_limit = days.unwrap_or(365);
-    let start_date = chrono::Utc::now()
-        .checked_sub_signed(chrono::Duration::days(days_limit as i64))
-        .unwrap_or_else(chrono::Utc::now)
-        .format("%Y-%m-%d")
-        .to_string();
+    let empty_stats = |date: String| DailyStats {
+        date,
+        pomodoros_completed: 0,
+        total_work_time: 0,
+        tasks_completed: 0,
+        goal_progress: None,
+    };
 
-    let mut stmt = conn
-        .prepare(
-            "SELECT DATE(started_at) as date, COUNT(*) as count
-             FROM pomodoro_sessions 
-             WHERE session_type = 'work' 
-               AND interrupted = 0 
-               AND completed_at IS NOT NULL
-               AND DATE(started_at) >= ?1
-             GROUP BY DATE(started_at)
-             ORDER BY date ASC",
-        )
-        .map_err(|e| format!("Database error: {}", e))?;
+    let tags = crate::tags::normalize_tag_filter(tags);
+    let mut stats = if !tags.is_empty() {
+        let stats = tagged_daily_stats(&conn, &tags, Some(&date))?;
+        stats
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| empty_stats(date))
+    } else {
+        let mut stmt = conn
+            .prepare(
+                "SELECT date, pomodoros_completed, total_work_time, tasks_completed 
+                 FROM daily_stats WHERE date = ?1",
+            )
+            .map_err(|e| format!("Database error: {}", e))?;
 
-    let heatmap_iter = stmt
-        .query_map(params![start_date], |row| {
-            let count: u32 = row.get(1)?;
-            let level = match count {
-                0 => 0,
-                1..=2 => 1,
-                3..=5 => 2,
-                6..=9 => 3,
-                _ => 4,
-            };
-            Ok(HeatmapPoint {
-                date: row.get(0)?,
-                count,
-                level,
-            })
-        })
-        .map_err(|e| format!("Database error: {}", e))?;
+        let result = stmt.query_row([&date], daily_stats_from_row);
 
-    let mut heatmap = Vec::new();
-    for point in heatmap_iter {
-        heatmap.push(point.map_err(|e| format!("Database error: {}", e))?);
-    }
+        match result {
+            Ok(stats) => stats,
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                // No stats for this date, return empty stats
+                empty_stats(date)
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        }
+    };
+
+    let settings = load_settings(&app)?;
+    crate::streaks::attach_goal_progress(&conn, &settings, std::slice::from_mut(&mut stats))?;
 
-    Ok(heatmap)
+    Ok(stats)
 }
 
 #[tauri::command]
@@ -608,65 +1380,45 @@ pub async fn export_data(state: State<'_, DbPool>) -> Result<serde_json::Value,
     let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
 
     let mut tasks_stmt = conn
-        .prepare("SELECT * FROM tasks ORDER BY created_at DESC")
+        .prepare(&format!("SELECT {} FROM tasks ORDER BY created_at DESC", TASK_COLUMNS))
         .map_err(|e| format!("Database error: {}", e))?;
 
     let tasks_iter = tasks_stmt
-        .query_map([], |row| {
-            Ok(Task {
-                id: row.get(0)?,
-                text: row.get(1)?,
-                completed: row.get::<_, i32>(2)? != 0,
-                created_at: row.get(3)?,
-                completed_at: row.get(4)?,
-                priority: row.get::<_, Option<i32>>(5)?.unwrap_or(0),
-                estimated_pomodoros: row.get::<_, Option<i32>>(6)?.unwrap_or(1),
-                actual_pomodoros: row.get::<_, Option<i32>>(7)?.unwrap_or(0),
-            })
-        })
+        .query_map([], task_from_row)
         .map_err(|e| format!("Database error: {}", e))?;
 
     let mut tasks = Vec::new();
     for task in tasks_iter {
         tasks.push(task.map_err(|e| format!("Database error: {}", e))?);
     }
+    attach_task_details(&conn, &mut tasks)?;
 
     let mut sessions_stmt = conn
-        .prepare("SELECT * FROM pomodoro_sessions ORDER BY started_at DESC")
+        .prepare(&format!(
+            "SELECT {} FROM pomodoro_sessions ORDER BY started_at DESC",
+            SESSION_COLUMNS
+        ))
         .map_err(|e| format!("Database error: {}", e))?;
 
     let sessions_iter = sessions_stmt
-        .query_map([], |row| {
-            Ok(PomodoroSession {
-                id: row.get(0)?,
-                task_id: row.get(1)?,
-                session_type: row.get(2)?,
-                duration_minutes: row.get(3)?,
-                started_at: row.get(4)?,
-                completed_at: row.get(5)?,
-                interrupted: row.get::<_, i32>(6)? != 0,
-            })
-        })
+        .query_map([], session_from_row)
         .map_err(|e| format!("Database error: {}", e))?;
 
     let mut sessions = Vec::new();
     for session in sessions_iter {
         sessions.push(session.map_err(|e| format!("Database error: {}", e))?);
     }
+    crate::tags::attach_session_tags(&conn, &mut sessions)?;
 
     let mut stats_stmt = conn
-        .prepare("SELECT * FROM daily_stats ORDER BY date DESC")
+        .prepare(
+            "SELECT date, pomodoros_completed, total_work_time, tasks_completed
+             FROM daily_stats ORDER BY date DESC",
+        )
         .map_err(|e| format!("Database error: {}", e))?;
 
     let stats_iter = stats_stmt
-        .query_map([], |row| {
-            Ok(DailyStats {
-                date: row.get(0)?,
-                pomodoros_completed: row.get(1)?,
-                total_work_time: row.get(2)?,
-                tasks_completed: row.get(3)?,
-            })
-        })
+        .query_map([], daily_stats_from_row)
         .map_err(|e| format!("Database error: {}", e))?;
 
     let mut daily_stats = Vec::new();
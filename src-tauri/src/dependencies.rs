@@ -0,0 +1,212 @@
+use crate::database::{self, DbPool, Task};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskDependencies {
+    /// Tasks that must be finished before this one can start
+    pub blocked_by: Vec<Task>,
+    /// Tasks waiting on this one
+    pub blocking: Vec<Task>,
+}
+
+/// Ids of the unfinished tasks `task_id` depends on.
+pub fn open_blockers(conn: &rusqlite::Connection, task_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT d.depends_on_id FROM task_dependencies d
+             JOIN tasks t ON t.id = d.depends_on_id
+             WHERE d.task_id = ?1 AND t.completed = 0
+             ORDER BY t.created_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let ids = stmt
+        .query_map(params![task_id], |row| row.get(0))
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(ids)
+}
+
+/// Fill in `Task::blocked` and `Task::blocked_by` from unfinished dependencies.
+pub fn attach_blocked(conn: &rusqlite::Connection, tasks: &mut [Task]) -> Result<(), String> {
+    for task in tasks.iter_mut() {
+        task.blocked_by = open_blockers(conn, &task.id)?;
+        task.blocked = !task.completed && !task.blocked_by.is_empty();
+    }
+    Ok(())
+}
+
+/// Whether `from` already depends on `to`, directly or through other tasks.
+fn depends_on(conn: &rusqlite::Connection, from: &str, to: &str) -> Result<bool, String> {
+    conn.query_row(
+        "WITH RECURSIVE reachable(id) AS (
+             SELECT ?1
+             UNION
+             SELECT d.depends_on_id FROM task_dependencies d
+             JOIN reachable r ON d.task_id = r.id
+         )
+         SELECT EXISTS (SELECT 1 FROM reachable WHERE id = ?2)",
+        params![from, to],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Database error: {}", e))
+}
+
+/// Remove every dependency that involves `task_id`, in either direction.
+pub fn delete_task_dependencies(conn: &rusqlite::Connection, task_id: &str) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM task_dependencies WHERE task_id = ?1 OR depends_on_id = ?1",
+        params![task_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+    Ok(())
+}
+
+/// Mark `task_id` as blocked until `depends_on_id` is completed. Rejected when it
+/// would create a cycle.
+#[tauri::command]
+pub async fn add_task_dependency(
+    state: State<'_, DbPool>,
+    task_id: String,
+    depends_on_id: String,
+) -> Result<Task, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    if task_id == depends_on_id {
+        return Err("A task cannot depend on itself".to_string());
+    }
+
+    // Both tasks must exist
+    database::load_task(&conn, &task_id)?;
+    let prerequisite = database::load_task(&conn, &depends_on_id)?;
+
+    if depends_on(&conn, &depends_on_id, &task_id)? {
+        return Err(format!(
+            "\"{}\" already depends on this task; adding this dependency would create a cycle",
+            prerequisite.text
+        ));
+    }
+
+    conn.execute(
+        "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_id, created_at)
+         VALUES (?1, ?2, ?3)",
+        params![task_id, depends_on_id, chrono::Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    database::load_task(&conn, &task_id)
+}
+
+#[tauri::command]
+pub async fn remove_task_dependency(
+    state: State<'_, DbPool>,
+    task_id: String,
+    depends_on_id: String,
+) -> Result<Task, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    conn.execute(
+        "DELETE FROM task_dependencies WHERE task_id = ?1 AND depends_on_id = ?2",
+        params![task_id, depends_on_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    database::load_task(&conn, &task_id)
+}
+
+#[tauri::command]
+pub async fn get_task_dependencies(
+    state: State<'_, DbPool>,
+    task_id: String,
+) -> Result<TaskDependencies, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let blocked_by = database::load_tasks_where(
+        &conn,
+        "id IN (SELECT depends_on_id FROM task_dependencies WHERE task_id = ?1)",
+        "completed, created_at",
+        params![task_id],
+    )?;
+    let blocking = database::load_tasks_where(
+        &conn,
+        "id IN (SELECT task_id FROM task_dependencies WHERE depends_on_id = ?1)",
+        "completed, created_at",
+        params![task_id],
+    )?;
+
+    Ok(TaskDependencies {
+        blocked_by,
+        blocking,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &str)]) -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE task_dependencies (
+                task_id TEXT NOT NULL,
+                depends_on_id TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (task_id, depends_on_id)
+            )",
+            [],
+        )
+        .unwrap();
+        for (task_id, depends_on_id) in edges {
+            conn.execute(
+                "INSERT INTO task_dependencies (task_id, depends_on_id, created_at)
+                 VALUES (?1, ?2, '')",
+                params![task_id, depends_on_id],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    /// Whether making `task_id` depend on `depends_on_id` would close a cycle,
+    /// checked the way `add_task_dependency` does.
+    fn closes_cycle(conn: &rusqlite::Connection, task_id: &str, depends_on_id: &str) -> bool {
+        depends_on(conn, depends_on_id, task_id).unwrap()
+    }
+
+    #[test]
+    fn self_dependency_is_a_cycle() {
+        let conn = graph(&[]);
+        assert!(closes_cycle(&conn, "a", "a"));
+    }
+
+    #[test]
+    fn direct_cycle_is_rejected() {
+        let conn = graph(&[("a", "b")]);
+        assert!(closes_cycle(&conn, "b", "a"));
+        assert!(!closes_cycle(&conn, "a", "c"));
+    }
+
+    #[test]
+    fn cycle_through_a_third_task_is_rejected() {
+        let conn = graph(&[("a", "b"), ("b", "c")]);
+        assert!(closes_cycle(&conn, "c", "a"));
+        assert!(closes_cycle(&conn, "c", "b"));
+        // Shortcuts along the chain's direction are fine
+        assert!(!closes_cycle(&conn, "a", "c"));
+        assert!(!closes_cycle(&conn, "d", "a"));
+    }
+
+    #[test]
+    fn existing_cycles_do_not_loop_forever() {
+        let conn = graph(&[("a", "b"), ("b", "a")]);
+        assert!(depends_on(&conn, "a", "b").unwrap());
+        assert!(!depends_on(&conn, "a", "c").unwrap());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::database::{self, DbPool, Task};
+use chrono::{DateTime, Local};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::State;
+
+/// Fewer completed tasks than this under a tag and the overall bias is used instead.
+const MIN_TAG_SAMPLES: usize = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskEstimate {
+    pub task_id: String,
+    pub text: String,
+    pub estimated_pomodoros: i32,
+    pub actual_pomodoros: i32,
+    /// `actual - estimated`; positive means the task was underestimated
+    pub error: i32,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct EstimateSummary {
+    pub task_count: u32,
+    pub mean_absolute_error: f64,
+    /// Average of `actual - estimated`; positive means estimates run low
+    pub mean_error: f64,
+    pub underestimated_percent: f64,
+    pub overestimated_percent: f64,
+    pub accurate_percent: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupedEstimate {
+    /// Tag or project name, or `YYYY-MM` for the monthly trend
+    pub key: String,
+    pub summary: EstimateSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EstimationStats {
+    pub overall: EstimateSummary,
+    pub tasks: Vec<TaskEstimate>,
+    pub by_tag: Vec<GroupedEstimate>,
+    pub by_project: Vec<GroupedEstimate>,
+    pub by_month: Vec<GroupedEstimate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EstimateSuggestion {
+    pub estimated_pomodoros: i32,
+    pub suggested_pomodoros: i32,
+    /// Historical `actual / estimated` ratio the suggestion is scaled by
+    pub bias_ratio: f64,
+    /// Completed tasks the ratio was computed from
+    pub sample_size: u32,
+    /// Tag the ratio came from, when one had enough history
+    pub based_on_tag: Option<String>,
+}
+
+fn summarize(estimates: &[&TaskEstimate]) -> EstimateSummary {
+    if estimates.is_empty() {
+        return EstimateSummary::default();
+    }
+
+    let count = estimates.len() as f64;
+    let percent = |n: usize| (n as f64 / count * 1000.0).round() / 10.0;
+    let round = |value: f64| (value * 100.0).round() / 100.0;
+
+    EstimateSummary {
+        task_count: estimates.len() as u32,
+        mean_absolute_error: round(
+            estimates.iter().map(|e| e.error.abs() as f64).sum::<f64>() / count,
+        ),
+        mean_error: round(estimates.iter().map(|e| e.error as f64).sum::<f64>() / count),
+        underestimated_percent: percent(estimates.iter().filter(|e| e.error > 0).count()),
+        overestimated_percent: percent(estimates.iter().filter(|e| e.error < 0).count()),
+        accurate_percent: percent(estimates.iter().filter(|e| e.error == 0).count()),
+    }
+}
+
+/// Completed tasks that were actually worked on with the timer. Tasks ticked off
+/// without a single pomodoro say nothing about estimate quality and are skipped.
+fn estimated_tasks(
+    conn: &rusqlite::Connection,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Vec<Task>, String> {
+    database::load_tasks_where(
+        conn,
+        "completed = 1 AND COALESCE(actual_pomodoros, 0) > 0
+         AND (?1 IS NULL OR DATE(completed_at, 'localtime') >= ?1)
+         AND (?2 IS NULL OR DATE(completed_at, 'localtime') <= ?2)",
+        "completed_at DESC",
+        params![from, to],
+    )
+}
+
+fn task_estimate(task: &Task) -> TaskEstimate {
+    TaskEstimate {
+        task_id: task.id.clone(),
+        text: task.text.clone(),
+        estimated_pomodoros: task.estimated_pomodoros,
+        actual_pomodoros: task.actual_pomodoros,
+        error: task.actual_pomodoros - task.estimated_pomodoros,
+        completed_at: task.completed_at.clone(),
+    }
+}
+
+/// Actual over estimated pomodoros for a non-empty sample.
+fn bias_ratio(tasks: &[&Task]) -> f64 {
+    let estimated: i32 = tasks
+        .iter()
+        .map(|task| task.estimated_pomodoros.max(1))
+        .sum();
+    let actual: i32 = tasks.iter().map(|task| task.actual_pomodoros).sum();
+    actual as f64 / estimated as f64
+}
+
+/// Compare estimated and actual pomodoros for completed tasks, overall, per tag,
+/// per project and per month of completion. `from` / `to` bound the completion date.
+#[tauri::command]
+pub async fn get_estimation_stats(
+    state: State<'_, DbPool>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<EstimationStats, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let tasks = estimated_tasks(&conn, from.as_deref(), to.as_deref())?;
+    let estimates: Vec<TaskEstimate> = tasks.iter().map(task_estimate).collect();
+
+    let mut by_tag: BTreeMap<String, Vec<&TaskEstimate>> = BTreeMap::new();
+    let mut by_project: BTreeMap<String, Vec<&TaskEstimate>> = BTreeMap::new();
+    let mut by_month: BTreeMap<String, Vec<&TaskEstimate>> = BTreeMap::new();
+    for (task, estimate) in tasks.iter().zip(&estimates) {
+        for tag in &task.tags {
+            by_tag.entry(tag.clone()).or_default().push(estimate);
+        }
+        if let Some(project) = &task.project {
+            by_project
+                .entry(project.clone())
+                .or_default()
+                .push(estimate);
+        }
+        if let Some(completed_at) = task
+            .completed_at
+            .as_deref()
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+        {
+            let month = completed_at
+                .with_timezone(&Local)
+                .format("%Y-%m")
+                .to_string();
+            by_month.entry(month).or_default().push(estimate);
+        }
+    }
+
+    let group = |groups: BTreeMap<String, Vec<&TaskEstimate>>| {
+        groups
+            .into_iter()
+            .map(|(key, estimates)| GroupedEstimate {
+                key,
+                summary: summarize(&estimates),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    Ok(EstimationStats {
+        overall: summarize(&estimates.iter().collect::<Vec<_>>()),
+        by_tag: group(by_tag),
+        by_project: group(by_project),
+        by_month: group(by_month),
+        tasks: estimates,
+    })
+}
+
+/// Scale a new task's estimate by how far off the user's past estimates were.
+///
+/// When `tags` are given and one of them has enough completed tasks, that tag's
+/// history is used; otherwise all completed tasks are.
+#[tauri::command]
+pub async fn suggest_estimate(
+    state: State<'_, DbPool>,
+    estimated_pomodoros: i32,
+    tags: Option<Vec<String>>,
+) -> Result<EstimateSuggestion, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let estimated_pomodoros = estimated_pomodoros.max(1);
+    let tasks = estimated_tasks(&conn, None, None)?;
+
+    // Prefer the tag with the most history, as long as it has enough to be meaningful
+    let mut best_tag: Option<(String, Vec<&Task>)> = None;
+    for tag in crate::tags::normalize_tag_filter(tags) {
+        let tagged: Vec<&Task> = tasks
+            .iter()
+            .filter(|task| task.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)))
+            .collect();
+        let more_history = best_tag
+            .as_ref()
+            .map_or(true, |(_, best)| tagged.len() > best.len());
+        if tagged.len() >= MIN_TAG_SAMPLES && more_history {
+            best_tag = Some((tag, tagged));
+        }
+    }
+
+    let (based_on_tag, sample) = match best_tag {
+        Some((tag, tagged)) => (Some(tag), tagged),
+        None => (None, tasks.iter().collect()),
+    };
+
+    let ratio = if sample.is_empty() {
+        1.0
+    } else {
+        bias_ratio(&sample)
+    };
+
+    Ok(EstimateSuggestion {
+        estimated_pomodoros,
+        suggested_pomodoros: ((estimated_pomodoros as f64 * ratio).round() as i32).max(1),
+        bias_ratio: (ratio * 100.0).round() / 100.0,
+        sample_size: sample.len() as u32,
+        based_on_tag,
+    })
+}
@@ -0,0 +1,286 @@
+use crate::database::{self, DbPool, PomodoroSession};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+
+/// Score of a work session that was interrupted before its end, before penalties.
+const INTERRUPTED_BASE: f64 = 40.0;
+/// Points lost per logged interruption.
+const INTERRUPTION_PENALTY: f64 = 10.0;
+const MAX_INTERRUPTION_PENALTY: f64 = 40.0;
+/// Points lost per percent of the planned length spent paused.
+const PAUSE_PENALTY: f64 = 1.0;
+const MAX_PAUSE_PENALTY: f64 = 30.0;
+/// Points lost per percent of the planned length added by extensions.
+const EXTENSION_PENALTY: f64 = 0.5;
+const MAX_EXTENSION_PENALTY: f64 = 15.0;
+/// Most minutes a single session can be extended by, in total.
+const MAX_EXTENSION_MINUTES: u32 = 240;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreGrouping {
+    Day,
+    Task,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FocusScoreGroup {
+    /// Local `YYYY-MM-DD` or task id; `None` for sessions without a task
+    pub key: Option<String>,
+    pub label: String,
+    /// Scored work sessions in the group
+    pub sessions: u32,
+    pub average_score: f64,
+    pub min_score: u8,
+    pub max_score: u8,
+}
+
+/// 0–100 score for a finished work session: 100 for running to the end, 40 for
+/// an interrupted one, minus penalties for logged interruptions, time spent
+/// paused and minutes added by extensions. `None` for breaks and sessions that
+/// are still running.
+pub fn focus_score(session: &PomodoroSession) -> Option<u8> {
+    let finished = session.completed_at.is_some() || session.interrupted;
+    if session.session_type != "work" || !finished {
+        return None;
+    }
+
+    let base = if session.interrupted {
+        INTERRUPTED_BASE
+    } else {
+        100.0
+    };
+    let planned_seconds = session
+        .duration_minutes
+        .saturating_sub(session.extended_minutes)
+        .max(1) as f64
+        * 60.0;
+
+    let interruptions =
+        (session.interruptions as f64 * INTERRUPTION_PENALTY).min(MAX_INTERRUPTION_PENALTY);
+    let pause = (session.paused_seconds as f64 / planned_seconds * 100.0 * PAUSE_PENALTY)
+        .min(MAX_PAUSE_PENALTY);
+    let extension =
+        (session.extended_minutes as f64 * 60.0 / planned_seconds * 100.0 * EXTENSION_PENALTY)
+            .min(MAX_EXTENSION_PENALTY);
+
+    Some(
+        (base - interruptions - pause - extension)
+            .clamp(0.0, 100.0)
+            .round() as u8,
+    )
+}
+
+fn ensure_running(conn: &rusqlite::Connection, session_id: &str) -> Result<Option<String>, String> {
+    let (running, paused_at): (bool, Option<String>) = conn
+        .query_row(
+            "SELECT completed_at IS NULL AND interrupted = 0, paused_at
+             FROM pomodoro_sessions WHERE id = ?1",
+            params![session_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Session not found: {}", session_id),
+            e => format!("Database error: {}", e),
+        })?;
+
+    if !running {
+        return Err("Session has already ended".to_string());
+    }
+    Ok(paused_at)
+}
+
+/// Add the time since `paused_at` to the session's paused total and clear the pause.
+pub fn close_pause(conn: &rusqlite::Connection, session_id: &str) -> Result<(), String> {
+    let paused_at: Option<String> = conn
+        .query_row(
+            "SELECT paused_at FROM pomodoro_sessions WHERE id = ?1",
+            params![session_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let Some(paused_at) = paused_at else {
+        return Ok(());
+    };
+
+    let seconds = chrono::DateTime::parse_from_rfc3339(&paused_at)
+        .map(|paused_at| (chrono::Utc::now() - paused_at.with_timezone(&chrono::Utc)).num_seconds())
+        .unwrap_or(0)
+        .max(0);
+
+    conn.execute(
+        "UPDATE pomodoro_sessions SET paused_seconds = paused_seconds + ?1, paused_at = NULL
+         WHERE id = ?2",
+        params![seconds, session_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn pause_session(state: State<'_, DbPool>, session_id: String) -> Result<(), String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    if ensure_running(&conn, &session_id)?.is_some() {
+        return Err("Session is already paused".to_string());
+    }
+
+    conn.execute(
+        "UPDATE pomodoro_sessions SET paused_at = ?1 WHERE id = ?2",
+        params![chrono::Utc::now().to_rfc3339(), session_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn resume_session(state: State<'_, DbPool>, session_id: String) -> Result<(), String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    if ensure_running(&conn, &session_id)?.is_none() {
+        return Err("Session is not paused".to_string());
+    }
+
+    close_pause(&conn, &session_id)
+}
+
+/// Add `minutes` to a running session. The extension counts toward its length
+/// and focused time, but lowers its focus score. A session can be extended by
+/// at most `MAX_EXTENSION_MINUTES` in total.
+#[tauri::command]
+pub async fn extend_session(
+    state: State<'_, DbPool>,
+    session_id: String,
+    minutes: u32,
+) -> Result<(), String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    if minutes == 0 {
+        return Err("Extension must be at least one minute".to_string());
+    }
+    if minutes > MAX_EXTENSION_MINUTES {
+        return Err(format!(
+            "A session can be extended by at most {} minutes",
+            MAX_EXTENSION_MINUTES
+        ));
+    }
+    ensure_running(&conn, &session_id)?;
+
+    let updated = conn
+        .execute(
+            "UPDATE pomodoro_sessions
+             SET duration_minutes = duration_minutes + ?1, extended_minutes = extended_minutes + ?1
+             WHERE id = ?2 AND extended_minutes + ?1 <= ?3",
+            params![minutes, session_id, MAX_EXTENSION_MINUTES],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    if updated == 0 {
+        return Err(format!(
+            "A session can be extended by at most {} minutes in total",
+            MAX_EXTENSION_MINUTES
+        ));
+    }
+
+    Ok(())
+}
+
+/// Average focus score of finished work sessions per local day (oldest first)
+/// or per task (most sessions first). `from` / `to` are inclusive local dates.
+#[tauri::command]
+pub async fn get_focus_scores(
+    state: State<'_, DbPool>,
+    group_by: ScoreGrouping,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<FocusScoreGroup>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let from = from
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?
+        .map(|date| date.format("%Y-%m-%d").to_string());
+    let to = to
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?
+        .map(|date| date.format("%Y-%m-%d").to_string());
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}, DATE(started_at, 'localtime'),
+                    (SELECT text FROM tasks WHERE tasks.id = pomodoro_sessions.task_id)
+             FROM pomodoro_sessions
+             WHERE session_type = 'work'
+               AND (interrupted = 1 OR completed_at IS NOT NULL)
+               AND (?1 IS NULL OR DATE(started_at, 'localtime') >= ?1)
+               AND (?2 IS NULL OR DATE(started_at, 'localtime') <= ?2)",
+            database::SESSION_COLUMNS
+        ))
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![from, to], |row| {
+            Ok((
+                database::session_from_row(row)?,
+                row.get::<_, String>(12)?,
+                row.get::<_, Option<String>>(13)?,
+            ))
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut groups: HashMap<Option<String>, (FocusScoreGroup, u32)> = HashMap::new();
+    for row in rows {
+        let (session, day, text) = row.map_err(|e| format!("Database error: {}", e))?;
+        let Some(score) = session.focus_score else {
+            continue;
+        };
+
+        let (key, label) = match group_by {
+            ScoreGrouping::Day => (Some(day.clone()), day),
+            ScoreGrouping::Task => (
+                session.task_id.clone(),
+                text.unwrap_or_else(|| "No task".to_string()),
+            ),
+        };
+        let (group, total) = groups.entry(key.clone()).or_insert_with(|| {
+            (
+                FocusScoreGroup {
+                    key,
+                    label,
+                    sessions: 0,
+                    average_score: 0.0,
+                    min_score: score,
+                    max_score: score,
+                },
+                0,
+            )
+        });
+        group.sessions += 1;
+        group.min_score = group.min_score.min(score);
+        group.max_score = group.max_score.max(score);
+        *total += score as u32;
+    }
+
+    let mut groups: Vec<FocusScoreGroup> = groups
+        .into_values()
+        .map(|(mut group, total)| {
+            group.average_score = (total as f64 / group.sessions as f64 * 10.0).round() / 10.0;
+            group
+        })
+        .collect();
+    match group_by {
+        ScoreGrouping::Day => groups.sort_by(|a, b| a.label.cmp(&b.label)),
+        ScoreGrouping::Task => {
+            groups.sort_by(|a, b| b.sessions.cmp(&a.sessions).then(a.label.cmp(&b.label)))
+        }
+    }
+
+    Ok(groups)
+}
@@ -0,0 +1,298 @@
+use crate::database::{self, DbPool, PomodoroSession};
+use chrono::{Datelike, Duration, NaiveDate};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::State;
+
+const PLANT_COLUMNS: &str =
+    "id, session_id, task_id, species, stage, focus_score, planted_at, date";
+
+/// Focus score a completed session needs for its plant to bloom.
+const BLOOM_SCORE: u8 = 90;
+/// Below this the plant stays a sprout.
+const GROWING_SCORE: u8 = 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlantSpecies {
+    /// Sessions shorter than 25 minutes
+    Tomato,
+    /// 25 to 49 minutes
+    Sakura,
+    /// 50 minutes or more
+    Pine,
+}
+
+impl PlantSpecies {
+    fn for_minutes(minutes: u32) -> Self {
+        match minutes {
+            0..=24 => PlantSpecies::Tomato,
+            25..=49 => PlantSpecies::Sakura,
+            _ => PlantSpecies::Pine,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            PlantSpecies::Tomato => "tomato",
+            PlantSpecies::Sakura => "sakura",
+            PlantSpecies::Pine => "pine",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "tomato" => PlantSpecies::Tomato,
+            "pine" => PlantSpecies::Pine,
+            _ => PlantSpecies::Sakura,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GrowthStage {
+    Sprout,
+    Growing,
+    Blooming,
+    /// The session was interrupted
+    Withered,
+}
+
+impl GrowthStage {
+    fn for_session(session: &PomodoroSession) -> Self {
+        if session.interrupted {
+            return GrowthStage::Withered;
+        }
+        match session.focus_score.unwrap_or(0) {
+            score if score >= BLOOM_SCORE => GrowthStage::Blooming,
+            score if score >= GROWING_SCORE => GrowthStage::Growing,
+            _ => GrowthStage::Sprout,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            GrowthStage::Sprout => "sprout",
+            GrowthStage::Growing => "growing",
+            GrowthStage::Blooming => "blooming",
+            GrowthStage::Withered => "withered",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "growing" => GrowthStage::Growing,
+            "blooming" => GrowthStage::Blooming,
+            "withered" => GrowthStage::Withered,
+            _ => GrowthStage::Sprout,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Plant {
+    pub id: String,
+    /// Work session the plant grew from
+    pub session_id: String,
+    pub task_id: Option<String>,
+    pub species: PlantSpecies,
+    pub stage: GrowthStage,
+    pub focus_score: Option<u8>,
+    pub planted_at: String,
+    /// Local `YYYY-MM-DD` the session started on
+    pub date: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GardenPeriod {
+    Day,
+    /// Monday to Sunday
+    Week,
+    /// Calendar year
+    Year,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GardenDay {
+    pub date: String,
+    /// Plants that didn't wither
+    pub grown: u32,
+    pub withered: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Garden {
+    pub period: GardenPeriod,
+    pub from: String,
+    pub to: String,
+    /// Oldest first
+    pub plants: Vec<Plant>,
+    pub grown: u32,
+    pub withered: u32,
+    /// Days in the period with at least one plant, oldest first
+    pub days: Vec<GardenDay>,
+}
+
+fn plant_from_row(row: &rusqlite::Row) -> rusqlite::Result<Plant> {
+    Ok(Plant {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        task_id: row.get(2)?,
+        species: PlantSpecies::parse(&row.get::<_, String>(3)?),
+        stage: GrowthStage::parse(&row.get::<_, String>(4)?),
+        focus_score: row.get(5)?,
+        planted_at: row.get(6)?,
+        date: row.get(7)?,
+    })
+}
+
+fn load_sessions(
+    conn: &rusqlite::Connection,
+    condition: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<(PomodoroSession, String)>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}, DATE(started_at, 'localtime') FROM pomodoro_sessions
+             WHERE session_type = 'work' AND (completed_at IS NOT NULL OR interrupted = 1)
+               AND {}",
+            database::SESSION_COLUMNS,
+            condition
+        ))
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let session_iter = stmt
+        .query_map(params, |row| {
+            Ok((database::session_from_row(row)?, row.get(12)?))
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut sessions = Vec::new();
+    for session in session_iter {
+        sessions.push(session.map_err(|e| format!("Database error: {}", e))?);
+    }
+
+    Ok(sessions)
+}
+
+fn plant(conn: &rusqlite::Connection, session: &PomodoroSession, date: &str) -> Result<(), String> {
+    // Interrupted sessions have no completion time
+    let planted_at = session
+        .completed_at
+        .clone()
+        .unwrap_or_else(|| session.started_at.clone());
+
+    // Replacing keeps one plant per session if a session is completed again
+    conn.execute(
+        "INSERT INTO garden_plants (id, session_id, task_id, species, stage, focus_score, planted_at, date)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(session_id) DO UPDATE SET
+             task_id = excluded.task_id, species = excluded.species, stage = excluded.stage,
+             focus_score = excluded.focus_score, date = excluded.date",
+        params![
+            uuid::Uuid::new_v4().to_string(),
+            session.id,
+            session.task_id,
+            PlantSpecies::for_minutes(session.duration_minutes).as_str(),
+            GrowthStage::for_session(session).as_str(),
+            session.focus_score,
+            planted_at,
+            date
+        ],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(())
+}
+
+/// Grow (or wither) the plant for a work session that just ended.
+pub fn plant_session(conn: &rusqlite::Connection, session_id: &str) -> Result<(), String> {
+    for (session, date) in load_sessions(conn, "id = ?1", params![session_id])? {
+        plant(conn, &session, &date)?;
+    }
+    Ok(())
+}
+
+/// Plant a garden for every finished work session, for databases created
+/// before the garden existed.
+pub fn plant_all(conn: &rusqlite::Connection) -> Result<(), String> {
+    for (session, date) in load_sessions(conn, "1", [])? {
+        plant(conn, &session, &date)?;
+    }
+    Ok(())
+}
+
+fn period_bounds(period: GardenPeriod, anchor: NaiveDate) -> (NaiveDate, NaiveDate) {
+    match period {
+        GardenPeriod::Day => (anchor, anchor),
+        GardenPeriod::Week => {
+            let start = anchor - Duration::days(anchor.weekday().num_days_from_monday() as i64);
+            (start, start + Duration::days(6))
+        }
+        GardenPeriod::Year => (
+            NaiveDate::from_ymd_opt(anchor.year(), 1, 1).unwrap_or(anchor),
+            NaiveDate::from_ymd_opt(anchor.year(), 12, 31).unwrap_or(anchor),
+        ),
+    }
+}
+
+/// The garden grown during the day, week or year containing `date` (default today).
+#[tauri::command]
+pub async fn get_garden(
+    state: State<'_, DbPool>,
+    period: GardenPeriod,
+    date: Option<String>,
+) -> Result<Garden, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let anchor = match date {
+        Some(date) => crate::schedule::parse_date(&date)?,
+        None => crate::schedule::today(),
+    };
+    let (start, end) = period_bounds(period, anchor);
+    let from = start.format("%Y-%m-%d").to_string();
+    let to = end.format("%Y-%m-%d").to_string();
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM garden_plants WHERE date BETWEEN ?1 AND ?2 ORDER BY planted_at",
+            PLANT_COLUMNS
+        ))
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let plant_iter = stmt
+        .query_map(params![from, to], plant_from_row)
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut plants = Vec::new();
+    for plant in plant_iter {
+        plants.push(plant.map_err(|e| format!("Database error: {}", e))?);
+    }
+
+    let mut days: BTreeMap<String, GardenDay> = BTreeMap::new();
+    for plant in &plants {
+        let day = days.entry(plant.date.clone()).or_insert_with(|| GardenDay {
+            date: plant.date.clone(),
+            ..Default::default()
+        });
+        if plant.stage == GrowthStage::Withered {
+            day.withered += 1;
+        } else {
+            day.grown += 1;
+        }
+    }
+
+    Ok(Garden {
+        period,
+        from,
+        to,
+        grown: days.values().map(|day| day.grown).sum(),
+        withered: days.values().map(|day| day.withered).sum(),
+        days: days.into_values().collect(),
+        plants,
+    })
+}
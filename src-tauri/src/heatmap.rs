@@ -0,0 +1,247 @@
+use crate::database::DbPool;
+use chrono::{Duration, NaiveDate};
+use rusqlite::params_from_iter;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Longest range a heatmap may cover, about ten years.
+pub const MAX_RANGE_DAYS: i64 = 3653;
+
+/// Number of non-empty levels; level 0 is reserved for days without activity.
+const LEVELS: usize = 4;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapMetric {
+    /// Completed, uninterrupted work sessions
+    #[default]
+    Sessions,
+    /// Minutes of completed, uninterrupted work sessions
+    FocusMinutes,
+    TasksCompleted,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HeatmapPoint {
+    pub date: String,
+    /// Value of the selected metric on this day
+    pub count: u32,
+    /// 0 for no activity, otherwise 1–4 by quartile of the active days in range
+    pub level: u8,
+}
+
+/// Upper bounds of levels 1 to 3, taken from the quartiles of the non-zero
+/// values; anything above the last bound is level 4.
+pub fn level_thresholds(values: &[u32]) -> Vec<u32> {
+    let mut active: Vec<u32> = values.iter().copied().filter(|value| *value > 0).collect();
+    if active.is_empty() {
+        return Vec::new();
+    }
+    active.sort_unstable();
+
+    (1..LEVELS)
+        .map(|quartile| {
+            // Nearest-rank quantile
+            let rank = (quartile * active.len()).div_ceil(LEVELS);
+            active[rank.max(1) - 1]
+        })
+        .collect()
+}
+
+pub fn level_for(value: u32, thresholds: &[u32]) -> u8 {
+    if value == 0 {
+        return 0;
+    }
+    let below = thresholds
+        .iter()
+        .take_while(|threshold| value > **threshold)
+        .count();
+    (below + 1) as u8
+}
+
+/// Metric value per local day between `from` and `to`, days without activity omitted.
+fn daily_values(
+    conn: &rusqlite::Connection,
+    metric: HeatmapMetric,
+    from: &str,
+    to: &str,
+    tags: &[String],
+) -> Result<HashMap<String, u32>, String> {
+    let sql = match metric {
+        HeatmapMetric::Sessions | HeatmapMetric::FocusMinutes => format!(
+            "SELECT DATE(started_at, 'localtime') AS day, {}
+             FROM pomodoro_sessions s
+             WHERE session_type = 'work'
+               AND interrupted = 0
+               AND completed_at IS NOT NULL
+               AND DATE(started_at, 'localtime') BETWEEN ?1 AND ?2
+               AND {}
+             GROUP BY day",
+            if metric == HeatmapMetric::Sessions {
+                "COUNT(*)"
+            } else {
+                "SUM(duration_minutes)"
+            },
+            crate::tags::session_tag_condition("s", 3, tags.len())
+        ),
+        HeatmapMetric::TasksCompleted => format!(
+            "SELECT DATE(completed_at, 'localtime') AS day, COUNT(*)
+             FROM tasks
+             WHERE completed = 1
+               AND DATE(completed_at, 'localtime') BETWEEN ?1 AND ?2
+               AND {}
+             GROUP BY day",
+            crate::tags::task_tag_condition("tasks.id", 3, tags.len())
+        ),
+    };
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut values = vec![from.to_string(), to.to_string()];
+    values.extend(tags.iter().cloned());
+
+    let rows = stmt
+        .query_map(params_from_iter(values.iter()), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut days = HashMap::new();
+    for row in rows {
+        let (day, value) = row.map_err(|e| format!("Database error: {}", e))?;
+        days.insert(day, value);
+    }
+
+    Ok(days)
+}
+
+/// One point per day from `from` to `to` inclusive, zero-filled, with levels
+/// relative to the active days in the range.
+pub fn build_heatmap(
+    conn: &rusqlite::Connection,
+    metric: HeatmapMetric,
+    from: NaiveDate,
+    to: NaiveDate,
+    tags: &[String],
+) -> Result<Vec<HeatmapPoint>, String> {
+    if to < from {
+        return Err("`to` must not be before `from`".to_string());
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        return Err(format!(
+            "Heatmap range is too long; it can cover at most {} days",
+            MAX_RANGE_DAYS
+        ));
+    }
+
+    let days = daily_values(
+        conn,
+        metric,
+        &from.format(DATE_FORMAT).to_string(),
+        &to.format(DATE_FORMAT).to_string(),
+        tags,
+    )?;
+    let thresholds = level_thresholds(&days.values().copied().collect::<Vec<_>>());
+
+    let mut heatmap = Vec::with_capacity(((to - from).num_days() + 1) as usize);
+    for day in from.iter_days().take_while(|day| *day <= to) {
+        let date = day.format(DATE_FORMAT).to_string();
+        let count = days.get(&date).copied().unwrap_or(0);
+        heatmap.push(HeatmapPoint {
+            date,
+            count,
+            level: level_for(count, &thresholds),
+        });
+    }
+
+    Ok(heatmap)
+}
+
+/// Resolve the requested range: explicit `from` / `to` win, otherwise the
+/// `days` (default 365) days ending today. `build_heatmap` rejects ranges longer
+/// than `MAX_RANGE_DAYS`.
+pub fn heatmap_range(
+    days: Option<u32>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<(NaiveDate, NaiveDate), String> {
+    let to = match to {
+        Some(to) => crate::schedule::parse_date(&to)?,
+        None => crate::schedule::today(),
+    };
+    let from = match from {
+        Some(from) => crate::schedule::parse_date(&from)?,
+        None => to
+            .checked_sub_signed(Duration::days(days.unwrap_or(365) as i64))
+            .ok_or_else(|| "Heatmap range starts before the earliest supported date".to_string())?,
+    };
+    Ok((from, to))
+}
+
+/// Daily activity for a calendar heatmap. `metric` defaults to sessions; the
+/// range can span several years.
+#[tauri::command]
+pub async fn get_focus_heatmap(
+    state: State<'_, DbPool>,
+    days: Option<u32>,
+    tags: Option<Vec<String>>,
+    metric: Option<HeatmapMetric>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<HeatmapPoint>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let (from, to) = heatmap_range(days, from, to)?;
+    let tags = crate::tags::normalize_tag_filter(tags);
+
+    build_heatmap(&conn, metric.unwrap_or_default(), from, to, &tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels(values: &[u32]) -> Vec<u8> {
+        let thresholds = level_thresholds(values);
+        values
+            .iter()
+            .map(|value| level_for(*value, &thresholds))
+            .collect()
+    }
+
+    #[test]
+    fn all_zero_days_have_no_thresholds() {
+        assert!(level_thresholds(&[]).is_empty());
+        assert!(level_thresholds(&[0, 0, 0]).is_empty());
+        assert_eq!(levels(&[0, 0, 0]), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn single_active_day_is_the_lowest_level() {
+        assert_eq!(level_thresholds(&[0, 5, 0]), vec![5, 5, 5]);
+        assert_eq!(levels(&[0, 5, 0]), vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn spread_values_fill_every_level() {
+        let values = [1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(level_thresholds(&values), vec![2, 4, 6]);
+        assert_eq!(levels(&values), vec![1, 1, 2, 2, 3, 3, 4, 4]);
+    }
+
+    #[test]
+    fn values_on_a_boundary_stay_in_the_lower_level() {
+        // Ties collapse the first two quartiles onto the same bound
+        let values = [1, 1, 1, 1, 2, 3, 4, 5];
+        assert_eq!(level_thresholds(&values), vec![1, 1, 3]);
+        assert_eq!(levels(&values), vec![1, 1, 1, 1, 3, 3, 4, 4]);
+
+        assert_eq!(levels(&[3, 3, 3, 3]), vec![1, 1, 1, 1]);
+    }
+}
@@ -0,0 +1,328 @@
+use crate::database::{self, DbPool};
+use crate::heatmap::{self, HeatmapMetric, HeatmapPoint};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use tauri::{AppHandle, State};
+
+const CELL: u32 = 11;
+const GAP: u32 = 3;
+const STEP: u32 = CELL + GAP;
+/// Room for the weekday labels on the left.
+const LEFT: u32 = 32;
+/// Room for the title and month labels above the grid.
+const TOP: u32 = 44;
+/// Room for the legend below the grid.
+const BOTTOM: u32 = 30;
+const MARGIN: u32 = 12;
+/// Keeps the title and legend readable for short ranges.
+const MIN_WIDTH: u32 = 360;
+const FONT: &str = "-apple-system, 'Segoe UI', Helvetica, Arial, sans-serif";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageFormat {
+    Svg,
+    Png,
+}
+
+/// Which heatmap to draw; the data arguments mean the same as for `get_focus_heatmap`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HeatmapImageRequest {
+    pub days: Option<u32>,
+    pub tags: Option<Vec<String>>,
+    pub metric: Option<HeatmapMetric>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// `light` or `dark`; defaults to the app theme
+    pub theme: Option<String>,
+}
+
+struct Palette {
+    background: &'static str,
+    text: &'static str,
+    /// Fill for levels 0–4
+    levels: [&'static str; 5],
+}
+
+const LIGHT: Palette = Palette {
+    background: "#ffffff",
+    text: "#59636e",
+    levels: ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"],
+};
+
+const DARK: Palette = Palette {
+    background: "#0d1117",
+    text: "#8b949e",
+    levels: ["#161b22", "#0e4429", "#006d32", "#26a641", "#39d353"],
+};
+
+fn palette(theme: &str) -> &'static Palette {
+    if theme.eq_ignore_ascii_case("dark") {
+        &DARK
+    } else {
+        &LIGHT
+    }
+}
+
+fn metric_label(metric: HeatmapMetric, value: u32) -> &'static str {
+    match (metric, value == 1) {
+        (HeatmapMetric::Sessions, true) => "pomodoro",
+        (HeatmapMetric::Sessions, false) => "pomodoros",
+        (HeatmapMetric::FocusMinutes, true) => "focused minute",
+        (HeatmapMetric::FocusMinutes, false) => "focused minutes",
+        (HeatmapMetric::TasksCompleted, true) => "task completed",
+        (HeatmapMetric::TasksCompleted, false) => "tasks completed",
+    }
+}
+
+/// Draw `points` as a calendar with one column per week (Sunday on top), month
+/// labels, weekday labels and a less-to-more legend.
+pub fn render_svg(points: &[HeatmapPoint], metric: HeatmapMetric, theme: &str) -> String {
+    let palette = palette(theme);
+    let dates: Vec<Option<NaiveDate>> = points
+        .iter()
+        .map(|point| crate::schedule::parse_date(&point.date).ok())
+        .collect();
+    let first = dates.iter().flatten().next().copied();
+    let weeks = match (first, dates.iter().flatten().last()) {
+        (Some(first), Some(last)) => {
+            let offset = first.weekday().num_days_from_sunday() as i64;
+            (((*last - first).num_days() + offset) / 7 + 1) as u32
+        }
+        _ => 0,
+    };
+
+    let width = (LEFT + weeks * STEP + MARGIN).max(MIN_WIDTH);
+    let height = TOP + 7 * STEP + BOTTOM;
+    let total: u32 = points.iter().map(|point| point.count).sum();
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"{font}\" font-size=\"10\">",
+        w = width,
+        h = height,
+        font = FONT
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+        palette.background
+    );
+    if let (Some(from), Some(to)) = (points.first(), points.last()) {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" fill=\"{}\" font-size=\"12\">{} {} from {} to {}</text>",
+            LEFT,
+            MARGIN + 8,
+            palette.text,
+            total,
+            metric_label(metric, total),
+            from.date,
+            to.date
+        );
+    }
+
+    for (row, label) in [(1, "Mon"), (3, "Wed"), (5, "Fri")] {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>",
+            MARGIN.min(LEFT - 4),
+            TOP + row * STEP + CELL - 2,
+            palette.text,
+            label
+        );
+    }
+
+    let Some(first) = first else {
+        svg.push_str("</svg>\n");
+        return svg;
+    };
+    let offset = first.weekday().num_days_from_sunday() as i64;
+
+    let mut last_month_column: Option<u32> = None;
+    for (point, date) in points.iter().zip(&dates) {
+        let Some(date) = date else {
+            continue;
+        };
+        let index = (*date - first).num_days() + offset;
+        let (column, row) = ((index / 7) as u32, (index % 7) as u32);
+        let (x, y) = (LEFT + column * STEP, TOP + row * STEP);
+
+        // Label each month above the week holding its first day, and the partial
+        // first month only if it is shown for long enough to have room
+        let starts_month = date.day() == 1 || (*date == first && date.day() <= 21);
+        if starts_month && last_month_column.map_or(true, |last| column >= last + 3) {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>",
+                x,
+                TOP - 6,
+                palette.text,
+                date.format("%b")
+            );
+            last_month_column = Some(column);
+        }
+
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" rx=\"2\" fill=\"{}\"><title>{}: {} {}</title></rect>",
+            x,
+            y,
+            palette.levels[point.level.min(4) as usize],
+            point.date,
+            point.count,
+            metric_label(metric, point.count),
+            c = CELL
+        );
+    }
+
+    // Legend, right-aligned under the grid
+    let legend_y = TOP + 7 * STEP + 8;
+    let legend_x = width - MARGIN - 5 * STEP - 30;
+    let _ = writeln!(
+        svg,
+        "<text x=\"{}\" y=\"{}\" fill=\"{}\" text-anchor=\"end\">Less</text>",
+        legend_x - 4,
+        legend_y + CELL - 2,
+        palette.text
+    );
+    for (level, fill) in palette.levels.iter().enumerate() {
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" rx=\"2\" fill=\"{}\"/>",
+            legend_x + level as u32 * STEP,
+            legend_y,
+            fill,
+            c = CELL
+        );
+    }
+    let _ = writeln!(
+        svg,
+        "<text x=\"{}\" y=\"{}\" fill=\"{}\">More</text>",
+        legend_x + 5 * STEP + 2,
+        legend_y + CELL - 2,
+        palette.text
+    );
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// System fonts, loaded on the first PNG export and shared by later ones.
+fn system_fonts() -> Arc<resvg::usvg::fontdb::Database> {
+    static FONTS: OnceLock<Arc<resvg::usvg::fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = resvg::usvg::fontdb::Database::new();
+            fonts.load_system_fonts();
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+/// Rasterize an SVG at `scale` times its size, using the system fonts for text.
+pub fn render_png(svg: &str, scale: f32) -> Result<Vec<u8>, String> {
+    let options = resvg::usvg::Options {
+        fontdb: system_fonts(),
+        ..Default::default()
+    };
+
+    let tree = resvg::usvg::Tree::from_str(svg, &options)
+        .map_err(|e| format!("Failed to parse heatmap SVG: {}", e))?;
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or("Invalid image scale")?;
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or("Failed to allocate heatmap image")?;
+
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    pixmap
+        .encode_png()
+        .map_err(|e| format!("Failed to encode heatmap PNG: {}", e))
+}
+
+fn heatmap_svg(
+    app: &AppHandle,
+    conn: &rusqlite::Connection,
+    request: HeatmapImageRequest,
+) -> Result<String, String> {
+    let (from, to) = heatmap::heatmap_range(request.days, request.from, request.to)?;
+    let tags = crate::tags::normalize_tag_filter(request.tags);
+    let metric = request.metric.unwrap_or_default();
+    let points = heatmap::build_heatmap(conn, metric, from, to, &tags)?;
+
+    let theme = match request.theme {
+        Some(theme) => theme,
+        None => database::load_settings(app)?.theme,
+    };
+
+    Ok(render_svg(&points, metric, &theme))
+}
+
+#[tauri::command]
+pub async fn render_focus_heatmap_svg(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+    request: HeatmapImageRequest,
+) -> Result<String, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    heatmap_svg(&app, &conn, request)
+}
+
+/// Write the heatmap to `path` as SVG or PNG; without `format` it follows the
+/// file extension. `scale` (default 2) only applies to PNG. Returns the path written.
+#[tauri::command]
+pub async fn write_focus_heatmap_image(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+    request: HeatmapImageRequest,
+    path: String,
+    format: Option<ImageFormat>,
+    scale: Option<f32>,
+) -> Result<String, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let target = Path::new(&path);
+    let format = format.unwrap_or_else(|| {
+        let png = target
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+        if png {
+            ImageFormat::Png
+        } else {
+            ImageFormat::Svg
+        }
+    });
+
+    let svg = heatmap_svg(&app, &conn, request)?;
+    let bytes = match format {
+        ImageFormat::Svg => svg.into_bytes(),
+        ImageFormat::Png => render_png(&svg, scale.unwrap_or(2.0).clamp(0.5, 8.0))?,
+    };
+
+    if let Some(parent) = target
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create image directory: {}", e))?;
+    }
+    std::fs::write(target, bytes).map_err(|e| format!("Failed to write heatmap image: {}", e))?;
+
+    Ok(path)
+}
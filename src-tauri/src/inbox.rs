@@ -0,0 +1,218 @@
+use crate::database::{DbPool, Task};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+/// Event emitted when a work session ends and there are untriaged inbox items.
+pub const REVIEW_EVENT: &str = "inbox-review";
+
+const ITEM_COLUMNS: &str = "id, text, session_id, captured_at, status, task_id, resolved_at";
+
+/// A thought captured during a focus session to deal with later.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InboxItem {
+    pub id: String,
+    pub text: String,
+    /// Session that was running when the item was captured
+    pub session_id: Option<String>,
+    pub captured_at: String,
+    /// `open`, `converted` or `dismissed`
+    pub status: String,
+    /// Task created from the item when it was converted
+    pub task_id: Option<String>,
+    pub resolved_at: Option<String>,
+}
+
+fn item_from_row(row: &rusqlite::Row) -> rusqlite::Result<InboxItem> {
+    Ok(InboxItem {
+        id: row.get(0)?,
+        text: row.get(1)?,
+        session_id: row.get(2)?,
+        captured_at: row.get(3)?,
+        status: row.get(4)?,
+        task_id: row.get(5)?,
+        resolved_at: row.get(6)?,
+    })
+}
+
+fn load_item(conn: &rusqlite::Connection, item_id: &str) -> Result<InboxItem, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM inbox_items WHERE id = ?1", ITEM_COLUMNS),
+        params![item_id],
+        item_from_row,
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => format!("Inbox item not found: {}", item_id),
+        e => format!("Database error: {}", e),
+    })
+}
+
+pub fn open_items(conn: &rusqlite::Connection) -> Result<Vec<InboxItem>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM inbox_items WHERE status = 'open' ORDER BY captured_at",
+            ITEM_COLUMNS
+        ))
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let item_iter = stmt
+        .query_map([], item_from_row)
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut items = Vec::new();
+    for item in item_iter {
+        items.push(item.map_err(|e| format!("Database error: {}", e))?);
+    }
+
+    Ok(items)
+}
+
+/// Ask the frontend to review open items now that a break is starting.
+pub fn prompt_review(app: &AppHandle, conn: &rusqlite::Connection) -> Result<(), String> {
+    let items = open_items(conn)?;
+    if !items.is_empty() {
+        app.emit(REVIEW_EVENT, items)
+            .map_err(|e| format!("Failed to emit inbox review: {}", e))?;
+    }
+    Ok(())
+}
+
+fn resolve(
+    conn: &rusqlite::Connection,
+    item_id: &str,
+    status: &str,
+    task_id: Option<&str>,
+) -> Result<(), String> {
+    let updated = conn
+        .execute(
+            "UPDATE inbox_items SET status = ?1, task_id = ?2, resolved_at = ?3
+             WHERE id = ?4 AND status = 'open'",
+            params![status, task_id, chrono::Utc::now().to_rfc3339(), item_id],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if updated == 0 {
+        load_item(conn, item_id)?;
+        return Err("Inbox item has already been triaged".to_string());
+    }
+    Ok(())
+}
+
+/// Capture a stray thought. Without `session_id` it is linked to the running
+/// session, if any.
+#[tauri::command]
+pub async fn capture_inbox_item(
+    state: State<'_, DbPool>,
+    text: String,
+    session_id: Option<String>,
+) -> Result<InboxItem, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("Inbox item text cannot be empty".to_string());
+    }
+
+    let session_id = match session_id {
+        Some(session_id) => Some(session_id),
+        None => conn
+            .query_row(
+                "SELECT id FROM pomodoro_sessions
+                 WHERE completed_at IS NULL AND interrupted = 0
+                 ORDER BY started_at DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .ok(),
+    };
+
+    let item = InboxItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        text,
+        session_id,
+        captured_at: chrono::Utc::now().to_rfc3339(),
+        status: "open".to_string(),
+        task_id: None,
+        resolved_at: None,
+    };
+
+    conn.execute(
+        "INSERT INTO inbox_items (id, text, session_id, captured_at, status)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            item.id,
+            item.text,
+            item.session_id,
+            item.captured_at,
+            item.status
+        ],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(item)
+}
+
+/// Open items, oldest first; with `include_resolved` triaged items are listed too.
+#[tauri::command]
+pub async fn get_inbox(
+    state: State<'_, DbPool>,
+    include_resolved: Option<bool>,
+) -> Result<Vec<InboxItem>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    if !include_resolved.unwrap_or(false) {
+        return open_items(&conn);
+    }
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM inbox_items ORDER BY status != 'open', captured_at",
+            ITEM_COLUMNS
+        ))
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let item_iter = stmt
+        .query_map([], item_from_row)
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut items = Vec::new();
+    for item in item_iter {
+        items.push(item.map_err(|e| format!("Database error: {}", e))?);
+    }
+
+    Ok(items)
+}
+
+/// Turn an inbox item into a task. The text is read with the quick-add syntax,
+/// so `Call Sam !2 due:tomorrow` captured mid-session keeps its attributes.
+#[tauri::command]
+pub async fn convert_inbox_item(state: State<'_, DbPool>, item_id: String) -> Result<Task, String> {
+    let pool = state.inner();
+    let mut conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let item = load_item(&conn, &item_id)?;
+    if item.status != "open" {
+        return Err("Inbox item has already been triaged".to_string());
+    }
+
+    // If the item was triaged meanwhile, resolving fails and the task is rolled back
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let task = crate::quick_add::create_task(&tx, &item.text)?;
+    resolve(&tx, &item_id, "converted", Some(&task.id))?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(task)
+}
+
+#[tauri::command]
+pub async fn dismiss_inbox_item(state: State<'_, DbPool>, item_id: String) -> Result<(), String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    resolve(&conn, &item_id, "dismissed", None)
+}
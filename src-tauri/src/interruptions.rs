@@ -0,0 +1,280 @@
+use crate::database::DbPool;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::State;
+
+pub const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Interruption {
+    pub id: String,
+    pub session_id: String,
+    /// `internal` (the classic `'` mark) or `external` (`-`)
+    pub kind: String,
+    pub occurred_at: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct InterruptionCount {
+    /// Hour `00`–`23` or weekday name, depending on the grouping
+    pub key: String,
+    pub internal: u32,
+    pub external: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskInterruptions {
+    /// `None` for sessions without a task
+    pub task_id: Option<String>,
+    pub text: Option<String>,
+    pub internal: u32,
+    pub external: u32,
+    /// Work sessions on the task that had at least one interruption
+    pub interrupted_sessions: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InterruptionStats {
+    pub internal: u32,
+    pub external: u32,
+    /// One entry per local hour of the day, `00` to `23`
+    pub by_hour: Vec<InterruptionCount>,
+    /// One entry per weekday, Sunday first
+    pub by_weekday: Vec<InterruptionCount>,
+    /// Most interrupted tasks first, ties by task id
+    pub by_task: Vec<TaskInterruptions>,
+}
+
+/// Accept the kind names or the classic marks.
+fn parse_kind(kind: &str) -> Result<&'static str, String> {
+    match kind.trim() {
+        "internal" | "'" => Ok("internal"),
+        "external" | "-" => Ok("external"),
+        other => Err(format!(
+            "Unknown interruption kind '{}', expected internal or external",
+            other
+        )),
+    }
+}
+
+fn interruption_from_row(row: &rusqlite::Row) -> rusqlite::Result<Interruption> {
+    Ok(Interruption {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        kind: row.get(2)?,
+        occurred_at: row.get(3)?,
+        note: row.get(4)?,
+    })
+}
+
+/// Record an interruption on a session that is still running.
+#[tauri::command]
+pub async fn log_interruption(
+    state: State<'_, DbPool>,
+    session_id: String,
+    kind: String,
+    note: Option<String>,
+) -> Result<Interruption, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let kind = parse_kind(&kind)?;
+    let active: bool = conn
+        .query_row(
+            "SELECT completed_at IS NULL AND interrupted = 0 FROM pomodoro_sessions WHERE id = ?1",
+            params![session_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Session not found: {}", session_id),
+            e => format!("Database error: {}", e),
+        })?;
+    if !active {
+        return Err("Interruptions can only be logged during an active session".to_string());
+    }
+
+    let interruption = Interruption {
+        id: uuid::Uuid::new_v4().to_string(),
+        session_id,
+        kind: kind.to_string(),
+        occurred_at: chrono::Utc::now().to_rfc3339(),
+        note: note
+            .map(|note| note.trim().to_string())
+            .filter(|note| !note.is_empty()),
+    };
+
+    conn.execute(
+        "INSERT INTO interruptions (id, session_id, kind, occurred_at, note)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            interruption.id,
+            interruption.session_id,
+            interruption.kind,
+            interruption.occurred_at,
+            interruption.note
+        ],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(interruption)
+}
+
+#[tauri::command]
+pub async fn get_session_interruptions(
+    state: State<'_, DbPool>,
+    session_id: String,
+) -> Result<Vec<Interruption>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, session_id, kind, occurred_at, note FROM interruptions
+             WHERE session_id = ?1 ORDER BY occurred_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let interruption_iter = stmt
+        .query_map(params![session_id], interruption_from_row)
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut interruptions = Vec::new();
+    for interruption in interruption_iter {
+        interruptions.push(interruption.map_err(|e| format!("Database error: {}", e))?);
+    }
+
+    Ok(interruptions)
+}
+
+/// Interruption counts by local hour, weekday and task. `from` / `to` are
+/// inclusive `YYYY-MM-DD` bounds on the local date of the interruption.
+#[tauri::command]
+pub async fn get_interruption_stats(
+    state: State<'_, DbPool>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<InterruptionStats, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let from = from
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?
+        .map(|date| date.format("%Y-%m-%d").to_string());
+    let to = to
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?
+        .map(|date| date.format("%Y-%m-%d").to_string());
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT i.kind,
+                    CAST(strftime('%H', i.occurred_at, 'localtime') AS INTEGER),
+                    CAST(strftime('%w', i.occurred_at, 'localtime') AS INTEGER),
+                    s.task_id, t.text, i.session_id
+             FROM interruptions i
+             JOIN pomodoro_sessions s ON s.id = i.session_id
+             LEFT JOIN tasks t ON t.id = s.task_id
+             WHERE (?1 IS NULL OR DATE(i.occurred_at, 'localtime') >= ?1)
+               AND (?2 IS NULL OR DATE(i.occurred_at, 'localtime') <= ?2)",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![from, to], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, usize>(1)?,
+                row.get::<_, usize>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut by_hour: Vec<InterruptionCount> = (0..24)
+        .map(|hour| InterruptionCount {
+            key: format!("{:02}", hour),
+            ..Default::default()
+        })
+        .collect();
+    let mut by_weekday: Vec<InterruptionCount> = WEEKDAYS
+        .iter()
+        .map(|day| InterruptionCount {
+            key: day.to_string(),
+            ..Default::default()
+        })
+        .collect();
+    let mut by_task: HashMap<Option<String>, TaskInterruptions> = HashMap::new();
+    let mut seen_sessions: HashSet<String> = HashSet::new();
+    let (mut internal, mut external) = (0, 0);
+
+    for row in rows {
+        let (kind, hour, weekday, task_id, text, session_id) =
+            row.map_err(|e| format!("Database error: {}", e))?;
+        let is_internal = kind == "internal";
+        let bump = |count: &mut InterruptionCount| {
+            if is_internal {
+                count.internal += 1;
+            } else {
+                count.external += 1;
+            }
+        };
+
+        if is_internal {
+            internal += 1;
+        } else {
+            external += 1;
+        }
+        if let Some(count) = by_hour.get_mut(hour) {
+            bump(count);
+        }
+        if let Some(count) = by_weekday.get_mut(weekday) {
+            bump(count);
+        }
+
+        let entry = by_task
+            .entry(task_id.clone())
+            .or_insert_with(|| TaskInterruptions {
+                task_id,
+                text,
+                internal: 0,
+                external: 0,
+                interrupted_sessions: 0,
+            });
+        if is_internal {
+            entry.internal += 1;
+        } else {
+            entry.external += 1;
+        }
+        if seen_sessions.insert(session_id) {
+            entry.interrupted_sessions += 1;
+        }
+    }
+
+    let mut by_task: Vec<TaskInterruptions> = by_task.into_values().collect();
+    by_task.sort_by(|a, b| {
+        (b.internal + b.external)
+            .cmp(&(a.internal + a.external))
+            .then_with(|| a.task_id.cmp(&b.task_id))
+    });
+
+    Ok(InterruptionStats {
+        internal,
+        external,
+        by_hour,
+        by_weekday,
+        by_task,
+    })
+}
@@ -1,7 +1,30 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod achievements;
 mod audio;
+mod breaks;
+mod checklist;
 mod database;
+mod dependencies;
+mod estimates;
+mod focus_score;
+mod garden;
+mod heatmap;
+mod heatmap_image;
+mod inbox;
+mod interruptions;
+mod plain_text;
+mod plan;
+mod productivity;
+mod quick_add;
+mod recurrence;
+mod reports;
+mod schedule;
+mod search;
+mod streaks;
+mod summary;
+mod tags;
+mod task_query;
 
 use database::AppSettings;
 use std::fs;
@@ -79,26 +102,15 @@ async fn set_monk_mode(app: AppHandle, enabled: bool) -> Result<(), String> {
 
 #[tauri::command]
 async fn get_settings(app: tauri::AppHandle) -> Result<AppSettings, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    let settings_path = app_data_dir.join("settings.json");
-
-    if settings_path.exists() {
-        let settings_content = fs::read_to_string(settings_path)
-            .map_err(|e| format!("Failed to read settings: {}", e))?;
-
-        serde_json::from_str(&settings_content)
-            .map_err(|e| format!("Failed to parse settings: {}", e))
-    } else {
-        Ok(AppSettings::default())
-    }
+    database::load_settings(&app)
 }
 
 #[tauri::command]
-async fn save_settings(app: tauri::AppHandle, settings: AppSettings) -> Result<(), String> {
+async fn save_settings(app: tauri::AppHandle, mut settings: AppSettings) -> Result<(), String> {
+    settings.archive_completed_after_days = settings
+        .archive_completed_after_days
+        .map(|days| days.min(task_query::MAX_ARCHIVE_AFTER_DAYS));
+
     let app_data_dir = app
         .path()
         .app_data_dir()
@@ -134,14 +146,77 @@ fn main() {
             database::get_tasks,
             database::complete_task,
             database::update_task,
+            database::update_task_priority,
+            database::update_task_estimate,
+            database::update_task_notes,
             database::delete_task,
             database::start_pomodoro_session,
             database::complete_pomodoro_session,
+            database::set_session_reflection,
             database::get_task_with_stats,
             database::get_daily_stats,
             database::get_daily_stats_by_date,
-            database::get_focus_heatmap,
+            heatmap::get_focus_heatmap,
             database::export_data,
+            tags::get_tags,
+            tags::tag_task,
+            tags::untag_task,
+            tags::tag_session,
+            tags::untag_session,
+            tags::rename_tag,
+            tags::merge_tags,
+            checklist::add_checklist_item,
+            checklist::update_checklist_item,
+            checklist::complete_checklist_item,
+            checklist::delete_checklist_item,
+            checklist::reorder_checklist_items,
+            schedule::set_task_dates,
+            schedule::get_today_tasks,
+            schedule::get_upcoming_tasks,
+            schedule::get_overdue_tasks,
+            recurrence::set_task_recurrence,
+            recurrence::get_recurrence_history,
+            search::search,
+            estimates::get_estimation_stats,
+            estimates::suggest_estimate,
+            task_query::query_tasks,
+            task_query::archive_completed_tasks,
+            task_query::unarchive_task,
+            dependencies::add_task_dependency,
+            dependencies::remove_task_dependency,
+            dependencies::get_task_dependencies,
+            quick_add::parse_quick_add,
+            quick_add::quick_add_task,
+            plain_text::import_todo_txt,
+            plain_text::import_markdown,
+            plain_text::export_todo_txt,
+            plain_text::export_markdown,
+            plan::get_daily_plan,
+            plan::add_to_plan,
+            plan::remove_from_plan,
+            plan::reorder_plan,
+            plan::get_plan_review,
+            interruptions::log_interruption,
+            interruptions::get_session_interruptions,
+            interruptions::get_interruption_stats,
+            inbox::capture_inbox_item,
+            inbox::get_inbox,
+            inbox::convert_inbox_item,
+            inbox::dismiss_inbox_item,
+            reports::get_report,
+            streaks::get_streaks,
+            productivity::get_focus_distribution,
+            focus_score::pause_session,
+            focus_score::resume_session,
+            focus_score::extend_session,
+            focus_score::get_focus_scores,
+            breaks::get_break_stats,
+            summary::render_summary_report,
+            summary::write_summary_report,
+            heatmap_image::render_focus_heatmap_svg,
+            heatmap_image::write_focus_heatmap_image,
+            achievements::get_achievements,
+            garden::get_garden,
             get_settings,
             save_settings,
             update_status,
@@ -157,6 +232,19 @@ fn main() {
             let db_pool = database::initialize_database(&app.handle())
                 .map_err(|e| format!("Failed to initialize database: {}", e))?;
             
+
+            // Archive old completed tasks so they stop cluttering the task list
+            if let Some(days) = database::load_settings(&app.handle())
+                .ok()
+                .and_then(|s| s.archive_completed_after_days)
+            {
+                if let Ok(conn) = db_pool.get() {
+                    if let Err(e) = task_query::archive_completed_tasks_older_than(&conn, days) {
+                        eprintln!("Failed to archive completed tasks: {}", e);
+                    }
+                }
+            }
+
             app.manage(db_pool);
 
             // Initialize monk mode state
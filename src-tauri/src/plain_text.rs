@@ -0,0 +1,621 @@
+use crate::database::{self, DbPool, Task};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// A task as it can be written to or read from a plain-text file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextTask {
+    pub text: String,
+    pub completed: bool,
+    /// 0–3, as in `Task::priority`
+    pub priority: i32,
+    pub estimated_pomodoros: i32,
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+    pub due_date: Option<String>,
+    pub created_on: Option<NaiveDate>,
+    pub completed_on: Option<NaiveDate>,
+    /// `(text, completed)`; only the Markdown format carries checklists
+    pub checklist: Vec<(String, bool)>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportSummary {
+    pub imported: u32,
+    /// Non-blank lines that were not recognised as tasks
+    pub skipped: u32,
+}
+
+fn local_date(timestamp: &str) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|date| date.with_timezone(&Local).date_naive())
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, DATE_FORMAT).ok()
+}
+
+/// Local midnight of `date` as a UTC timestamp, for dates read from a file.
+fn date_to_timestamp(date: NaiveDate) -> String {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+        .earliest()
+        .map(|date| date.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now)
+        .to_rfc3339()
+}
+
+/// Project and tag names become single words in both formats.
+fn as_word(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join("-")
+}
+
+impl From<&Task> for TextTask {
+    fn from(task: &Task) -> Self {
+        TextTask {
+            text: task.text.clone(),
+            completed: task.completed,
+            priority: task.priority,
+            estimated_pomodoros: task.estimated_pomodoros,
+            project: task.project.clone(),
+            tags: task.tags.clone(),
+            due_date: task.due_date.clone(),
+            created_on: local_date(&task.created_at),
+            completed_on: task.completed_at.as_deref().and_then(local_date),
+            checklist: task
+                .checklist
+                .iter()
+                .map(|item| (item.text.clone(), item.completed))
+                .collect(),
+        }
+    }
+}
+
+// todo.txt: https://github.com/todotxt/todo.txt
+//
+// `(A)`–`(C)` map to priorities 3–1; lower letters are imported as priority 1.
+// `+project` sets the project (further projects stay in the text), `@context`
+// becomes a tag, and the `due:`, `est:` and `pri:` keys are understood. Task
+// text that would read as one of these is written with a leading backslash,
+// which is dropped again on import.
+
+fn priority_letter(priority: i32) -> Option<char> {
+    match priority {
+        3 => Some('A'),
+        2 => Some('B'),
+        1 => Some('C'),
+        _ => None,
+    }
+}
+
+fn letter_priority(letter: char) -> i32 {
+    match letter {
+        'A' => 3,
+        'B' => 2,
+        _ => 1,
+    }
+}
+
+/// `(A)` style priority marker.
+fn parse_priority_marker(word: &str) -> Option<char> {
+    let mut chars = word.chars();
+    match (chars.next(), chars.next(), chars.next(), chars.next()) {
+        (Some('('), Some(letter), Some(')'), None) if letter.is_ascii_uppercase() => Some(letter),
+        _ => None,
+    }
+}
+
+pub fn parse_todo_txt_line(line: &str) -> Option<TextTask> {
+    let mut words = line.split_whitespace().peekable();
+    let mut task = TextTask {
+        estimated_pomodoros: 1,
+        ..Default::default()
+    };
+
+    if words.peek() == Some(&"x") {
+        words.next();
+        task.completed = true;
+        task.completed_on = words.peek().and_then(|word| parse_date(word));
+        if task.completed_on.is_some() {
+            words.next();
+        }
+    } else if let Some(letter) = words.peek().and_then(|word| parse_priority_marker(word)) {
+        words.next();
+        task.priority = letter_priority(letter);
+    }
+
+    task.created_on = words.peek().and_then(|word| parse_date(word));
+    if task.created_on.is_some() {
+        words.next();
+    }
+
+    let mut text = Vec::new();
+    for word in words {
+        if let Some(literal) = word.strip_prefix('\\').filter(|w| !w.is_empty()) {
+            text.push(literal);
+            continue;
+        }
+        if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty()) {
+            if task.project.is_none() {
+                task.project = Some(project.to_string());
+                continue;
+            }
+        } else if let Some(tag) = word.strip_prefix('@').filter(|t| !t.is_empty()) {
+            task.tags.push(tag.to_string());
+            continue;
+        } else if let Some(date) = word.strip_prefix("due:").and_then(parse_date) {
+            task.due_date = Some(date.format(DATE_FORMAT).to_string());
+            continue;
+        } else if let Some(estimate) = word
+            .strip_prefix("est:")
+            .and_then(|n| n.parse::<i32>().ok())
+            .filter(|n| *n >= 1)
+        {
+            task.estimated_pomodoros = estimate;
+            continue;
+        } else if let Some(letter) = word
+            .strip_prefix("pri:")
+            .and_then(|p| p.chars().next())
+            .filter(|c| c.is_ascii_uppercase())
+        {
+            task.priority = letter_priority(letter);
+            continue;
+        }
+        text.push(word);
+    }
+
+    task.text = text.join(" ");
+    if task.text.is_empty() {
+        None
+    } else {
+        Some(task)
+    }
+}
+
+/// Escape words `parse_todo_txt_line` would otherwise treat as attributes, and a
+/// first word it would read as a completion mark, priority or date.
+fn escape_todo_txt(text: &str) -> String {
+    text.split_whitespace()
+        .enumerate()
+        .map(|(index, word)| {
+            let attribute = ["+", "@", "\\", "due:", "est:", "pri:"]
+                .iter()
+                .any(|prefix| word.starts_with(prefix));
+            let leading = index == 0
+                && (word == "x"
+                    || parse_priority_marker(word).is_some()
+                    || parse_date(word).is_some());
+            if attribute || leading {
+                format!("\\{}", word)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn format_todo_txt_line(task: &TextTask) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let letter = priority_letter(task.priority);
+
+    if task.completed {
+        parts.push("x".to_string());
+        if let Some(date) = task.completed_on {
+            parts.push(date.format(DATE_FORMAT).to_string());
+        }
+    } else if let Some(letter) = letter {
+        parts.push(format!("({})", letter));
+    }
+    if let Some(date) = task.created_on {
+        parts.push(date.format(DATE_FORMAT).to_string());
+    }
+
+    parts.push(escape_todo_txt(&task.text));
+    if let Some(project) = &task.project {
+        parts.push(format!("+{}", as_word(project)));
+    }
+    for tag in &task.tags {
+        parts.push(format!("@{}", as_word(tag)));
+    }
+    if let Some(due_date) = &task.due_date {
+        parts.push(format!("due:{}", due_date));
+    }
+    if task.estimated_pomodoros != 1 {
+        parts.push(format!("est:{}", task.estimated_pomodoros));
+    }
+    // Completed tasks can't carry a leading priority, so keep it as a key instead
+    if let (true, Some(letter)) = (task.completed, letter) {
+        parts.push(format!("pri:{}", letter));
+    }
+
+    parts.join(" ")
+}
+
+// Markdown: `- [ ]` / `- [x]` list items. Task attributes use the quick-add
+// syntax (`!2 ~3 #tag @project due:YYYY-MM-DD`); indented items below a task are
+// its checklist.
+
+/// Split `- [ ] text` into `(indented, completed, text)`.
+fn parse_markdown_item(line: &str) -> Option<(bool, bool, &str)> {
+    let indented = line.starts_with(' ') || line.starts_with('\t');
+    let rest = line.trim_start();
+    let rest = rest
+        .strip_prefix("- ")
+        .or_else(|| rest.strip_prefix("* "))
+        .or_else(|| rest.strip_prefix("+ "))?;
+    let (completed, text) = if let Some(text) = rest.strip_prefix("[ ]") {
+        (false, text)
+    } else if let Some(text) = rest
+        .strip_prefix("[x]")
+        .or_else(|| rest.strip_prefix("[X]"))
+    {
+        (true, text)
+    } else {
+        return None;
+    };
+    Some((indented, completed, text.trim()))
+}
+
+/// Parse a Markdown checklist; lines that are not list items are ignored and
+/// counted in the second value.
+pub fn parse_markdown(content: &str, today: NaiveDate) -> (Vec<TextTask>, u32) {
+    let mut tasks: Vec<TextTask> = Vec::new();
+    let mut skipped = 0;
+
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_markdown_item(line) {
+            Some((true, completed, text)) if !text.is_empty() && !tasks.is_empty() => {
+                if let Some(task) = tasks.last_mut() {
+                    task.checklist.push((text.to_string(), completed));
+                }
+            }
+            Some((_, completed, text)) => {
+                let parsed = crate::quick_add::parse(text, today);
+                if parsed.text.is_empty() {
+                    skipped += 1;
+                    continue;
+                }
+                tasks.push(TextTask {
+                    text: parsed.text,
+                    completed,
+                    priority: parsed.priority.unwrap_or(0),
+                    estimated_pomodoros: parsed.estimated_pomodoros.unwrap_or(1),
+                    project: parsed.project,
+                    tags: parsed.tags,
+                    due_date: parsed.due_date,
+                    created_on: None,
+                    completed_on: None,
+                    checklist: Vec::new(),
+                });
+            }
+            None => skipped += 1,
+        }
+    }
+
+    (tasks, skipped)
+}
+
+/// Escape words the quick-add parser would otherwise treat as attributes.
+fn escape_quick_add(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            if word.starts_with(['#', '@', '!', '~', '\\']) || word.starts_with("due:") {
+                format!("\\{}", word)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn format_markdown(tasks: &[TextTask]) -> String {
+    let mut out = String::new();
+    let check = |completed: bool| if completed { "x" } else { " " };
+
+    for task in tasks {
+        let mut parts = vec![escape_quick_add(&task.text)];
+        if task.priority != 0 {
+            parts.push(format!("!{}", task.priority));
+        }
+        if task.estimated_pomodoros != 1 {
+            parts.push(format!("~{}", task.estimated_pomodoros));
+        }
+        for tag in &task.tags {
+            parts.push(format!("#{}", as_word(tag)));
+        }
+        if let Some(project) = &task.project {
+            parts.push(format!("@{}", as_word(project)));
+        }
+        if let Some(due_date) = &task.due_date {
+            parts.push(format!("due:{}", due_date));
+        }
+        out.push_str(&format!(
+            "- [{}] {}\n",
+            check(task.completed),
+            parts.join(" ")
+        ));
+
+        for (text, completed) in &task.checklist {
+            out.push_str(&format!("  - [{}] {}\n", check(*completed), text));
+        }
+    }
+
+    out
+}
+
+fn insert_text_task(conn: &rusqlite::Connection, task: &TextTask) -> Result<(), String> {
+    let now = Utc::now().to_rfc3339();
+    let created_at = task
+        .created_on
+        .map(date_to_timestamp)
+        .unwrap_or_else(|| now.clone());
+    let completed_at = task.completed.then(|| {
+        task.completed_on
+            .map(date_to_timestamp)
+            .unwrap_or_else(|| now.clone())
+    });
+
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tasks (id, text, completed, created_at, completed_at, priority,
+                            estimated_pomodoros, actual_pomodoros, due_date, project)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0, ?8, ?9)",
+        params![
+            id,
+            task.text,
+            task.completed,
+            created_at,
+            completed_at,
+            task.priority,
+            task.estimated_pomodoros,
+            task.due_date,
+            task.project
+        ],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    for tag in &task.tags {
+        let tag_id = crate::tags::ensure_tag(conn, tag)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO task_tags (task_id, tag_id) VALUES (?1, ?2)",
+            params![id, tag_id],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    }
+
+    for (position, (text, completed)) in task.checklist.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO task_checklist_items (id, task_id, text, completed, completed_at, position, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                uuid::Uuid::new_v4().to_string(),
+                id,
+                text,
+                completed,
+                completed.then(|| now.clone()),
+                position as i64,
+                now
+            ],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    }
+
+    Ok(())
+}
+
+fn import_tasks(pool: &DbPool, tasks: &[TextTask], skipped: u32) -> Result<ImportSummary, String> {
+    let mut conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    for task in tasks {
+        insert_text_task(&tx, task)?;
+    }
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit import: {}", e))?;
+
+    Ok(ImportSummary {
+        imported: tasks.len() as u32,
+        skipped,
+    })
+}
+
+/// Tasks for export in creation order, so a re-import keeps the same order.
+fn export_tasks(pool: &DbPool, include_completed: bool) -> Result<Vec<TextTask>, String> {
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let tasks = database::load_tasks_where(
+        &conn,
+        "archived_at IS NULL AND (?1 OR completed = 0)",
+        "created_at ASC, id ASC",
+        params![include_completed],
+    )?;
+
+    Ok(tasks.iter().map(TextTask::from).collect())
+}
+
+#[tauri::command]
+pub async fn import_todo_txt(
+    state: State<'_, DbPool>,
+    content: String,
+) -> Result<ImportSummary, String> {
+    let mut tasks = Vec::new();
+    let mut skipped = 0;
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        match parse_todo_txt_line(line) {
+            Some(task) => tasks.push(task),
+            None => skipped += 1,
+        }
+    }
+
+    import_tasks(state.inner(), &tasks, skipped)
+}
+
+#[tauri::command]
+pub async fn import_markdown(
+    state: State<'_, DbPool>,
+    content: String,
+) -> Result<ImportSummary, String> {
+    let (tasks, skipped) = parse_markdown(&content, crate::schedule::today());
+    import_tasks(state.inner(), &tasks, skipped)
+}
+
+/// Export the task list (without archived tasks) as todo.txt lines.
+#[tauri::command]
+pub async fn export_todo_txt(
+    state: State<'_, DbPool>,
+    include_completed: Option<bool>,
+) -> Result<String, String> {
+    let tasks = export_tasks(state.inner(), include_completed.unwrap_or(true))?;
+
+    Ok(tasks
+        .iter()
+        .map(|task| format_todo_txt_line(task) + "\n")
+        .collect())
+}
+
+/// Export the task list (without archived tasks) as a Markdown checklist.
+#[tauri::command]
+pub async fn export_markdown(
+    state: State<'_, DbPool>,
+    include_completed: Option<bool>,
+) -> Result<String, String> {
+    let tasks = export_tasks(state.inner(), include_completed.unwrap_or(true))?;
+    Ok(format_markdown(&tasks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> Option<NaiveDate> {
+        parse_date(value)
+    }
+
+    fn sample_tasks() -> Vec<TextTask> {
+        vec![
+            TextTask {
+                text: "Write quarterly report".to_string(),
+                priority: 3,
+                estimated_pomodoros: 4,
+                project: Some("acme".to_string()),
+                tags: vec!["writing".to_string(), "office".to_string()],
+                due_date: Some("2026-10-16".to_string()),
+                created_on: date("2026-10-01"),
+                ..Default::default()
+            },
+            TextTask {
+                text: "Call the bank".to_string(),
+                completed: true,
+                priority: 2,
+                estimated_pomodoros: 1,
+                created_on: date("2026-09-28"),
+                completed_on: date("2026-10-02"),
+                ..Default::default()
+            },
+            TextTask {
+                text: "Plain task".to_string(),
+                estimated_pomodoros: 1,
+                created_on: date("2026-10-03"),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn todo_txt_round_trip() {
+        for task in sample_tasks() {
+            let line = format_todo_txt_line(&task);
+            assert_eq!(parse_todo_txt_line(&line), Some(task), "{}", line);
+        }
+    }
+
+    #[test]
+    fn todo_txt_round_trips_text_that_looks_like_syntax() {
+        let texts = [
+            "Email @bob about +1",
+            "Ask about due:friday est:3 pri:A",
+            "x marks the spot",
+            "(B) is a grade",
+            "2026-10-20 deadline",
+            "Keep \\backslashes",
+        ];
+        for (index, text) in texts.into_iter().enumerate() {
+            let task = TextTask {
+                text: text.to_string(),
+                completed: index % 2 == 1,
+                estimated_pomodoros: 1,
+                ..Default::default()
+            };
+            let line = format_todo_txt_line(&task);
+            assert_eq!(parse_todo_txt_line(&line), Some(task), "{}", line);
+        }
+    }
+
+    #[test]
+    fn todo_txt_parses_spec_examples() {
+        let task = parse_todo_txt_line(
+            "x 2026-10-02 2026-09-30 Review +Budget @phone @office due:2026-10-05 pri:A",
+        )
+        .unwrap();
+        assert!(task.completed);
+        assert_eq!(task.completed_on, date("2026-10-02"));
+        assert_eq!(task.created_on, date("2026-09-30"));
+        assert_eq!(task.text, "Review");
+        assert_eq!(task.project.as_deref(), Some("Budget"));
+        assert_eq!(task.tags, vec!["phone", "office"]);
+        assert_eq!(task.due_date.as_deref(), Some("2026-10-05"));
+        assert_eq!(task.priority, 3);
+
+        let task = parse_todo_txt_line("(D) Read https://example.com +a +b").unwrap();
+        assert_eq!(task.priority, 1);
+        assert_eq!(task.text, "Read https://example.com +b");
+        assert_eq!(task.project.as_deref(), Some("a"));
+
+        assert_eq!(parse_todo_txt_line("x 2026-10-02"), None);
+    }
+
+    #[test]
+    fn markdown_round_trip() {
+        let mut tasks = sample_tasks();
+        for task in &mut tasks {
+            // Markdown carries no dates besides the due date
+            task.created_on = None;
+            task.completed_on = None;
+        }
+        tasks[0].checklist = vec![
+            ("Gather numbers".to_string(), true),
+            ("Draft summary".to_string(), false),
+        ];
+        tasks[2].text = "Fix #12 and ping @bob !soon".to_string();
+
+        let markdown = format_markdown(&tasks);
+        let today = date("2026-10-14").unwrap();
+        assert_eq!(parse_markdown(&markdown, today), (tasks, 0), "{}", markdown);
+    }
+
+    #[test]
+    fn markdown_ignores_prose() {
+        let (tasks, skipped) = parse_markdown(
+            "# Groceries\n\nSome notes\n* [X] Milk\n- [ ] Eggs #shop\n\t- [ ] free range\n",
+            date("2026-10-14").unwrap(),
+        );
+        assert_eq!(skipped, 2);
+        assert_eq!(tasks.len(), 2);
+        assert!(tasks[0].completed);
+        assert_eq!(tasks[1].tags, vec!["shop"]);
+        assert_eq!(tasks[1].checklist, vec![("free range".to_string(), false)]);
+    }
+}
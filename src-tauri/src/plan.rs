@@ -0,0 +1,356 @@
+use crate::database::{self, AppSettings, DbPool, Task};
+use chrono::NaiveTime;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+
+/// How many pomodoros fit in the working day with the current cycle settings.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PlanCapacity {
+    pub working_minutes: u32,
+    pub pomodoros: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Commitment {
+    Under,
+    Balanced,
+    Over,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DailyPlan {
+    /// Local `YYYY-MM-DD`
+    pub date: String,
+    /// Planned tasks in plan order
+    pub tasks: Vec<Task>,
+    /// Sum of the planned tasks' `estimated_pomodoros`
+    pub planned_pomodoros: u32,
+    /// Estimated pomodoros still to do on planned tasks that aren't completed
+    pub remaining_pomodoros: u32,
+    pub capacity: PlanCapacity,
+    pub commitment: Commitment,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanReviewItem {
+    pub task_id: String,
+    pub text: String,
+    pub estimated_pomodoros: i32,
+    /// Pomodoros completed on this task during the planned day
+    pub actual_pomodoros: u32,
+    pub completed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanReview {
+    pub date: String,
+    pub items: Vec<PlanReviewItem>,
+    pub planned_pomodoros: u32,
+    /// Pomodoros completed that day on planned tasks
+    pub actual_pomodoros: u32,
+    /// Pomodoros completed that day on other tasks or without a task
+    pub unplanned_pomodoros: u32,
+    pub planned_tasks: u32,
+    pub completed_tasks: u32,
+    pub capacity: PlanCapacity,
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid time '{}', expected HH:MM", value))
+}
+
+/// Count the work sessions that fit between `work_day_start` and `work_day_end`,
+/// following the configured cycle of short and long breaks. An end before the
+/// start is an overnight working day that runs past midnight.
+pub fn capacity(settings: &AppSettings) -> Result<PlanCapacity, String> {
+    let start = parse_time(&settings.work_day_start)?;
+    let end = parse_time(&settings.work_day_end)?;
+    let working_minutes = (end - start).num_minutes().rem_euclid(24 * 60) as u32;
+
+    let mut pomodoros = 0;
+    let mut elapsed = 0;
+    if settings.work_duration > 0 {
+        while elapsed + settings.work_duration <= working_minutes {
+            elapsed += settings.work_duration;
+            pomodoros += 1;
+            let long_break = settings.sessions_until_long_break > 0
+                && pomodoros % settings.sessions_until_long_break == 0;
+            elapsed += if long_break {
+                settings.long_break_duration
+            } else {
+                settings.break_duration
+            };
+        }
+    }
+
+    Ok(PlanCapacity {
+        working_minutes,
+        pomodoros,
+    })
+}
+
+/// Normalize an optional date argument, defaulting to today.
+fn plan_date(date: Option<String>) -> Result<String, String> {
+    let date = match date {
+        Some(date) if !date.trim().is_empty() => crate::schedule::parse_date(&date)?,
+        _ => crate::schedule::today(),
+    };
+    Ok(date.format("%Y-%m-%d").to_string())
+}
+
+fn planned_tasks(conn: &rusqlite::Connection, date: &str) -> Result<Vec<Task>, String> {
+    database::load_tasks_where(
+        conn,
+        "id IN (SELECT task_id FROM daily_plan WHERE date = ?1)",
+        "(SELECT position FROM daily_plan WHERE date = ?1 AND task_id = tasks.id), created_at",
+        params![date],
+    )
+}
+
+/// Load the plan for `date` and compare it against `settings`' capacity.
+pub fn load_plan(
+    conn: &rusqlite::Connection,
+    date: &str,
+    settings: &AppSettings,
+) -> Result<DailyPlan, String> {
+    let tasks = planned_tasks(conn, date)?;
+    let capacity = capacity(settings)?;
+
+    let planned_pomodoros: u32 = tasks
+        .iter()
+        .map(|task| task.estimated_pomodoros.max(0) as u32)
+        .sum();
+    let remaining_pomodoros: u32 = tasks
+        .iter()
+        .filter(|task| !task.completed)
+        .map(|task| (task.estimated_pomodoros - task.actual_pomodoros).max(0) as u32)
+        .sum();
+    let commitment = match planned_pomodoros.cmp(&capacity.pomodoros) {
+        std::cmp::Ordering::Less => Commitment::Under,
+        std::cmp::Ordering::Equal => Commitment::Balanced,
+        std::cmp::Ordering::Greater => Commitment::Over,
+    };
+
+    Ok(DailyPlan {
+        date: date.to_string(),
+        tasks,
+        planned_pomodoros,
+        remaining_pomodoros,
+        capacity,
+        commitment,
+    })
+}
+
+/// Compare what was planned for `date` with the pomodoros actually completed that day.
+pub fn review_plan(
+    conn: &rusqlite::Connection,
+    date: &str,
+    settings: &AppSettings,
+) -> Result<PlanReview, String> {
+    let tasks = planned_tasks(conn, date)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT task_id, COUNT(*) FROM pomodoro_sessions
+             WHERE session_type = 'work' AND interrupted = 0 AND completed_at IS NOT NULL
+               AND DATE(started_at, 'localtime') = ?1
+             GROUP BY task_id",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let done: HashMap<Option<String>, u32> = stmt
+        .query_map(params![date], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let items: Vec<PlanReviewItem> = tasks
+        .iter()
+        .map(|task| PlanReviewItem {
+            task_id: task.id.clone(),
+            text: task.text.clone(),
+            estimated_pomodoros: task.estimated_pomodoros,
+            actual_pomodoros: done.get(&Some(task.id.clone())).copied().unwrap_or(0),
+            completed: task.completed,
+        })
+        .collect();
+
+    let actual_pomodoros: u32 = items.iter().map(|item| item.actual_pomodoros).sum();
+    let total_pomodoros: u32 = done.values().sum();
+
+    Ok(PlanReview {
+        date: date.to_string(),
+        planned_pomodoros: items
+            .iter()
+            .map(|item| item.estimated_pomodoros.max(0) as u32)
+            .sum(),
+        actual_pomodoros,
+        unplanned_pomodoros: total_pomodoros - actual_pomodoros,
+        planned_tasks: items.len() as u32,
+        completed_tasks: items.iter().filter(|item| item.completed).count() as u32,
+        capacity: capacity(settings)?,
+        items,
+    })
+}
+
+#[tauri::command]
+pub async fn get_daily_plan(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+    date: Option<String>,
+) -> Result<DailyPlan, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let settings = database::load_settings(&app)?;
+    load_plan(&conn, &plan_date(date)?, &settings)
+}
+
+/// Add a task to the end of the plan for `date` (default today).
+#[tauri::command]
+pub async fn add_to_plan(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+    task_id: String,
+    date: Option<String>,
+) -> Result<DailyPlan, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let date = plan_date(date)?;
+    database::load_task(&conn, &task_id)?;
+
+    conn.execute(
+        "INSERT OR IGNORE INTO daily_plan (date, task_id, position, added_at)
+         VALUES (?1, ?2, (SELECT COALESCE(MAX(position) + 1, 0) FROM daily_plan WHERE date = ?1), ?3)",
+        params![date, task_id, chrono::Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let settings = database::load_settings(&app)?;
+    load_plan(&conn, &date, &settings)
+}
+
+#[tauri::command]
+pub async fn remove_from_plan(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+    task_id: String,
+    date: Option<String>,
+) -> Result<DailyPlan, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let date = plan_date(date)?;
+    conn.execute(
+        "DELETE FROM daily_plan WHERE date = ?1 AND task_id = ?2",
+        params![date, task_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let settings = database::load_settings(&app)?;
+    load_plan(&conn, &date, &settings)
+}
+
+/// Reorder the plan for `date`. Listed tasks come first in the given order;
+/// planned tasks missing from `task_ids` keep their relative order after them.
+#[tauri::command]
+pub async fn reorder_plan(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+    task_ids: Vec<String>,
+    date: Option<String>,
+) -> Result<DailyPlan, String> {
+    let pool = state.inner();
+    let mut conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let date = plan_date(date)?;
+    let current = planned_tasks(&conn, &date)?
+        .into_iter()
+        .map(|task| task.id)
+        .collect();
+    database::write_order(
+        &mut conn,
+        "UPDATE daily_plan SET position = ?1 WHERE task_id = ?2 AND date = ?3",
+        &date,
+        task_ids,
+        current,
+    )?;
+
+    let settings = database::load_settings(&app)?;
+    load_plan(&conn, &date, &settings)
+}
+
+#[tauri::command]
+pub async fn get_plan_review(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+    date: Option<String>,
+) -> Result<PlanReview, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let settings = database::load_settings(&app)?;
+    review_plan(&conn, &plan_date(date)?, &settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(start: &str, end: &str) -> AppSettings {
+        AppSettings {
+            work_duration: 25,
+            break_duration: 5,
+            long_break_duration: 15,
+            sessions_until_long_break: 4,
+            work_day_start: start.to_string(),
+            work_day_end: end.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn capacity_of(start: &str, end: &str) -> (u32, u32) {
+        let capacity = capacity(&settings(start, end)).unwrap();
+        (capacity.working_minutes, capacity.pomodoros)
+    }
+
+    #[test]
+    fn counts_sessions_with_short_and_long_breaks() {
+        // Four sessions and three short breaks take 115 minutes, then a long break
+        assert_eq!(capacity_of("09:00", "10:55"), (115, 4));
+        assert_eq!(capacity_of("09:00", "11:34"), (154, 4));
+        assert_eq!(capacity_of("09:00", "11:35"), (155, 5));
+        assert_eq!(capacity_of("09:00", "17:00"), (480, 15));
+    }
+
+    #[test]
+    fn too_short_a_day_fits_nothing() {
+        assert_eq!(capacity_of("09:00", "09:24"), (24, 0));
+        assert_eq!(capacity_of("09:00", "09:00"), (0, 0));
+    }
+
+    #[test]
+    fn end_before_start_wraps_past_midnight() {
+        assert_eq!(capacity_of("22:00", "02:00"), (240, 7));
+        assert_eq!(capacity_of("23:30", "00:25"), (55, 2));
+    }
+
+    #[test]
+    fn zero_length_sessions_fit_nothing() {
+        let settings = AppSettings {
+            work_duration: 0,
+            ..settings("09:00", "17:00")
+        };
+        assert_eq!(capacity(&settings).unwrap().pomodoros, 0);
+    }
+
+    #[test]
+    fn rejects_malformed_times() {
+        assert!(capacity(&settings("9am", "17:00")).is_err());
+        assert!(capacity(&settings("09:00", "25:00")).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::database::DbPool;
+use crate::interruptions::WEEKDAYS;
+use rusqlite::params_from_iter;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FocusBin {
+    /// Hour `00`–`23` or weekday name, depending on the grouping
+    pub key: String,
+    /// Work sessions that ran to the end without being interrupted
+    pub completed: u32,
+    pub interrupted: u32,
+    /// `completed / (completed + interrupted)`, `None` when the bin is empty
+    pub completion_rate: Option<f64>,
+    /// Average length of the completed sessions, `None` when there are none
+    pub average_focus_minutes: Option<f64>,
+    #[serde(skip)]
+    focus_minutes: u32,
+}
+
+impl FocusBin {
+    fn new(key: String) -> Self {
+        Self {
+            key,
+            ..Default::default()
+        }
+    }
+
+    fn add(&mut self, interrupted: bool, duration_minutes: u32) {
+        if interrupted {
+            self.interrupted += 1;
+        } else {
+            self.completed += 1;
+            self.focus_minutes += duration_minutes;
+        }
+    }
+
+    fn finish(&mut self) {
+        let round = |value: f64| (value * 10.0).round() / 10.0;
+        let total = self.completed + self.interrupted;
+        self.completion_rate =
+            (total > 0).then(|| round(self.completed as f64 / total as f64 * 100.0));
+        self.average_focus_minutes =
+            (self.completed > 0).then(|| round(self.focus_minutes as f64 / self.completed as f64));
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FocusDistribution {
+    /// One entry per local hour the session started in, `00` to `23`
+    pub by_hour: Vec<FocusBin>,
+    /// One entry per weekday, Sunday first
+    pub by_weekday: Vec<FocusBin>,
+}
+
+/// Completed vs. interrupted work sessions by local start hour and weekday.
+/// `from` / `to` are inclusive `YYYY-MM-DD` bounds on the local start date.
+#[tauri::command]
+pub async fn get_focus_distribution(
+    state: State<'_, DbPool>,
+    from: Option<String>,
+    to: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<FocusDistribution, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let from = from
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?
+        .map(|date| date.format("%Y-%m-%d").to_string());
+    let to = to
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?
+        .map(|date| date.format("%Y-%m-%d").to_string());
+    let tags = crate::tags::normalize_tag_filter(tags);
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT CAST(strftime('%H', started_at, 'localtime') AS INTEGER),
+                    CAST(strftime('%w', started_at, 'localtime') AS INTEGER),
+                    interrupted, duration_minutes
+             FROM pomodoro_sessions s
+             WHERE session_type = 'work'
+               AND (interrupted = 1 OR completed_at IS NOT NULL)
+               AND (?1 IS NULL OR DATE(started_at, 'localtime') >= ?1)
+               AND (?2 IS NULL OR DATE(started_at, 'localtime') <= ?2)
+               AND {}",
+            crate::tags::session_tag_condition("s", 3, tags.len())
+        ))
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut values: Vec<Option<String>> = vec![from, to];
+    values.extend(tags.into_iter().map(Some));
+
+    let rows = stmt
+        .query_map(params_from_iter(values.iter()), |row| {
+            Ok((
+                row.get::<_, usize>(0)?,
+                row.get::<_, usize>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, u32>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut by_hour: Vec<FocusBin> = (0..24)
+        .map(|hour| FocusBin::new(format!("{:02}", hour)))
+        .collect();
+    let mut by_weekday: Vec<FocusBin> = WEEKDAYS
+        .iter()
+        .map(|day| FocusBin::new(day.to_string()))
+        .collect();
+
+    for row in rows {
+        let (hour, weekday, interrupted, duration_minutes) =
+            row.map_err(|e| format!("Database error: {}", e))?;
+        if let Some(bin) = by_hour.get_mut(hour) {
+            bin.add(interrupted, duration_minutes);
+        }
+        if let Some(bin) = by_weekday.get_mut(weekday) {
+            bin.add(interrupted, duration_minutes);
+        }
+    }
+
+    by_hour.iter_mut().for_each(FocusBin::finish);
+    by_weekday.iter_mut().for_each(FocusBin::finish);
+
+    Ok(FocusDistribution {
+        by_hour,
+        by_weekday,
+    })
+}
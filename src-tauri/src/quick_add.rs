@@ -0,0 +1,258 @@
+use crate::database::{self, DbPool, Task};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// The result of parsing a quick-add line, shown to the user before the task is created.
+///
+/// Syntax, in any order and mixed with the task text:
+/// - `!0`–`!3` priority
+/// - `~N` estimated pomodoros
+/// - `#tag` (repeatable)
+/// - `@project`
+/// - `due:DATE` where DATE is `today`, `tomorrow`, a weekday (`fri`, `friday`),
+///   `+3d` / `+2w`, or `YYYY-MM-DD`
+///
+/// A leading backslash keeps a word as plain text, e.g. `\#1`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ParsedTask {
+    pub text: String,
+    pub priority: Option<i32>,
+    pub estimated_pomodoros: Option<i32>,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    /// Resolved local `YYYY-MM-DD` date
+    pub due_date: Option<String>,
+    /// Tokens that looked like quick-add syntax but could not be parsed; they are
+    /// left in `text`
+    pub warnings: Vec<String>,
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Resolve a `due:` value relative to `today`. A bare weekday means the next
+/// such day, counting today.
+fn parse_due(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let value = value.to_lowercase();
+    match value.as_str() {
+        "today" | "tod" => return Some(today),
+        "tomorrow" | "tom" | "tmr" => return today.succ_opt(),
+        _ => {}
+    }
+
+    if let Some(weekday) = parse_weekday(&value) {
+        let ahead = (weekday.num_days_from_monday() as i64
+            - today.weekday().num_days_from_monday() as i64)
+            .rem_euclid(7);
+        return today.checked_add_signed(Duration::days(ahead));
+    }
+
+    if let Some(offset) = value.strip_prefix('+') {
+        let (index, unit) = offset.char_indices().last()?;
+        let count: i64 = offset[..index].parse().ok()?;
+        let offset = match unit {
+            'd' => Duration::try_days(count)?,
+            'w' => Duration::try_weeks(count)?,
+            _ => return None,
+        };
+        return today.checked_add_signed(offset);
+    }
+
+    NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok()
+}
+
+/// Split a quick-add line into task text and attributes. `today` anchors relative dates.
+pub fn parse(input: &str, today: NaiveDate) -> ParsedTask {
+    let mut parsed = ParsedTask::default();
+    let mut words: Vec<&str> = Vec::new();
+
+    for word in input.split_whitespace() {
+        if let Some(literal) = word.strip_prefix('\\') {
+            if !literal.is_empty() {
+                words.push(literal);
+            }
+            continue;
+        }
+
+        if let Some(value) = word.strip_prefix('!').filter(|v| !v.is_empty()) {
+            match value.parse::<i32>() {
+                Ok(priority) if (0..=3).contains(&priority) => parsed.priority = Some(priority),
+                _ => {
+                    parsed
+                        .warnings
+                        .push(format!("'{}' is not a priority; use !0 to !3", word));
+                    words.push(word);
+                }
+            }
+        } else if let Some(value) = word.strip_prefix('~').filter(|v| !v.is_empty()) {
+            match value.parse::<i32>() {
+                Ok(estimate) if estimate >= 1 => parsed.estimated_pomodoros = Some(estimate),
+                _ => {
+                    parsed.warnings.push(format!(
+                        "'{}' is not an estimate; use ~N with N of at least 1",
+                        word
+                    ));
+                    words.push(word);
+                }
+            }
+        } else if let Some(tag) = word.strip_prefix('#').filter(|v| !v.is_empty()) {
+            if !parsed.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                parsed.tags.push(tag.to_string());
+            }
+        } else if let Some(project) = word.strip_prefix('@').filter(|v| !v.is_empty()) {
+            parsed.project = Some(project.to_string());
+        } else if let Some(value) = word.strip_prefix("due:") {
+            match parse_due(value, today) {
+                Some(date) => parsed.due_date = Some(date.format("%Y-%m-%d").to_string()),
+                None => {
+                    parsed
+                        .warnings
+                        .push(format!("Could not understand the due date in '{}'", word));
+                    words.push(word);
+                }
+            }
+        } else {
+            words.push(word);
+        }
+    }
+
+    parsed.text = words.join(" ");
+    parsed
+}
+
+/// Preview how a quick-add line would be interpreted without creating anything.
+#[tauri::command]
+pub async fn parse_quick_add(input: String) -> Result<ParsedTask, String> {
+    Ok(parse(&input, crate::schedule::today()))
+}
+
+/// Create a task from a quick-add line.
+pub fn create_task(conn: &rusqlite::Connection, input: &str) -> Result<Task, String> {
+    let parsed = parse(input, crate::schedule::today());
+    if parsed.text.is_empty() {
+        return Err("Task text cannot be empty".to_string());
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tasks (id, text, completed, created_at, priority, estimated_pomodoros,
+                            actual_pomodoros, due_date, project)
+         VALUES (?1, ?2, 0, ?3, ?4, ?5, 0, ?6, ?7)",
+        params![
+            id,
+            parsed.text,
+            chrono::Utc::now().to_rfc3339(),
+            parsed.priority.unwrap_or(0),
+            parsed.estimated_pomodoros.unwrap_or(1),
+            parsed.due_date,
+            parsed.project
+        ],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    for tag in &parsed.tags {
+        let tag_id = crate::tags::ensure_tag(conn, tag)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO task_tags (task_id, tag_id) VALUES (?1, ?2)",
+            params![id, tag_id],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    }
+
+    database::load_task(conn, &id)
+}
+
+/// Create a task from a quick-add line such as `Write report !2 ~3 #writing @acme due:fri`.
+#[tauri::command]
+pub async fn quick_add_task(state: State<'_, DbPool>, input: String) -> Result<Task, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    create_task(&conn, &input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        // A Saturday
+        NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()
+    }
+
+    fn due(value: &str) -> Option<String> {
+        parse_due(value, today()).map(|date| date.format("%Y-%m-%d").to_string())
+    }
+
+    #[test]
+    fn relative_due_dates() {
+        assert_eq!(due("today").as_deref(), Some("2026-10-17"));
+        assert_eq!(due("TOM").as_deref(), Some("2026-10-18"));
+        assert_eq!(due("sat").as_deref(), Some("2026-10-17"));
+        assert_eq!(due("monday").as_deref(), Some("2026-10-19"));
+        assert_eq!(due("+3d").as_deref(), Some("2026-10-20"));
+        assert_eq!(due("+2w").as_deref(), Some("2026-10-31"));
+        assert_eq!(due("+0d").as_deref(), Some("2026-10-17"));
+        assert_eq!(due("2026-12-24").as_deref(), Some("2026-12-24"));
+    }
+
+    #[test]
+    fn invalid_due_dates() {
+        assert_eq!(due("+"), None);
+        assert_eq!(due("+d"), None);
+        assert_eq!(due("+3m"), None);
+        assert_eq!(due("+3日"), None);
+        assert_eq!(due("+日"), None);
+        assert_eq!(due("2026-02-30"), None);
+        assert_eq!(due("someday"), None);
+    }
+
+    #[test]
+    fn huge_offsets_do_not_overflow() {
+        assert_eq!(due("+999999999999d"), None);
+        assert_eq!(due("+999999999999w"), None);
+        assert_eq!(due("+9223372036854775807w"), None);
+    }
+
+    #[test]
+    fn parses_attributes_out_of_text() {
+        let parsed = parse("Write report !2 ~3 #work #Work @acme due:fri", today());
+        assert_eq!(parsed.text, "Write report");
+        assert_eq!(parsed.priority, Some(2));
+        assert_eq!(parsed.estimated_pomodoros, Some(3));
+        assert_eq!(parsed.tags, vec!["work".to_string()]);
+        assert_eq!(parsed.project.as_deref(), Some("acme"));
+        assert_eq!(parsed.due_date.as_deref(), Some("2026-10-23"));
+        assert!(parsed.warnings.is_empty());
+    }
+
+    #[test]
+    fn bad_tokens_stay_in_text_with_a_warning() {
+        let parsed = parse("Fix bug !7 ~0 due:+3日", today());
+        assert_eq!(parsed.text, "Fix bug !7 ~0 due:+3日");
+        assert_eq!(parsed.priority, None);
+        assert_eq!(parsed.estimated_pomodoros, None);
+        assert_eq!(parsed.due_date, None);
+        assert_eq!(parsed.warnings.len(), 3);
+    }
+
+    #[test]
+    fn escaped_words_are_literal() {
+        let parsed = parse("Reply to \\#1 and \\@bob", today());
+        assert_eq!(parsed.text, "Reply to #1 and @bob");
+        assert!(parsed.tags.is_empty());
+        assert_eq!(parsed.project, None);
+    }
+}
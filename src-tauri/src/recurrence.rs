@@ -0,0 +1,497 @@
+use crate::database::{self, DbPool, Task, TaskWithStats};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use rusqlite::{params, OptionalExtension};
+use std::fmt;
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// The supported subset of an iCalendar RRULE: `FREQ` (DAILY, WEEKLY, MONTHLY),
+/// `INTERVAL`, `BYDAY` (weekly only) and `BYMONTHDAY` (monthly only).
+///
+/// The shorthands `daily`, `weekdays`, `weekly` and `monthly` are accepted too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<Weekday>,
+    pub by_month_day: Option<u32>,
+}
+
+const WEEKDAY_CODES: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Mon),
+    ("TU", Weekday::Tue),
+    ("WE", Weekday::Wed),
+    ("TH", Weekday::Thu),
+    ("FR", Weekday::Fri),
+    ("SA", Weekday::Sat),
+    ("SU", Weekday::Sun),
+];
+
+fn weekday_code(day: Weekday) -> &'static str {
+    WEEKDAY_CODES
+        .iter()
+        .find(|(_, weekday)| *weekday == day)
+        .map(|(code, _)| *code)
+        .unwrap_or("MO")
+}
+
+impl RecurrenceRule {
+    fn new(frequency: Frequency) -> Self {
+        Self {
+            frequency,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: None,
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let value = value
+            .strip_prefix("RRULE:")
+            .or_else(|| value.strip_prefix("rrule:"))
+            .unwrap_or(value);
+
+        match value.to_ascii_lowercase().as_str() {
+            "daily" => return Ok(Self::new(Frequency::Daily)),
+            "weekly" => return Ok(Self::new(Frequency::Weekly)),
+            "monthly" => return Ok(Self::new(Frequency::Monthly)),
+            "weekdays" => {
+                return Ok(Self {
+                    by_day: vec![
+                        Weekday::Mon,
+                        Weekday::Tue,
+                        Weekday::Wed,
+                        Weekday::Thu,
+                        Weekday::Fri,
+                    ],
+                    ..Self::new(Frequency::Weekly)
+                })
+            }
+            _ => {}
+        }
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut by_day = Vec::new();
+        let mut by_month_day = None;
+
+        for part in value.split(';').filter(|part| !part.trim().is_empty()) {
+            let (key, val) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid recurrence rule part '{}'", part))?;
+            let val = val.trim().to_ascii_uppercase();
+
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match val.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        _ => return Err(format!("Unsupported recurrence frequency '{}'", val)),
+                    })
+                }
+                "INTERVAL" => {
+                    interval = val
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(|| format!("Invalid recurrence interval '{}'", val))?
+                }
+                "BYDAY" => {
+                    for code in val.split(',') {
+                        let day = WEEKDAY_CODES
+                            .iter()
+                            .find(|(name, _)| *name == code.trim())
+                            .map(|(_, day)| *day)
+                            .ok_or_else(|| format!("Invalid weekday '{}'", code))?;
+                        if !by_day.contains(&day) {
+                            by_day.push(day);
+                        }
+                    }
+                }
+                "BYMONTHDAY" => {
+                    by_month_day = Some(
+                        val.parse::<u32>()
+                            .ok()
+                            .filter(|day| (1..=31).contains(day))
+                            .ok_or_else(|| format!("Invalid month day '{}'", val))?,
+                    )
+                }
+                other => return Err(format!("Unsupported recurrence rule part '{}'", other)),
+            }
+        }
+
+        let frequency = frequency.ok_or_else(|| "Recurrence rule is missing FREQ".to_string())?;
+        if !by_day.is_empty() && frequency != Frequency::Weekly {
+            return Err("BYDAY is only supported for weekly rules".to_string());
+        }
+        if by_month_day.is_some() && frequency != Frequency::Monthly {
+            return Err("BYMONTHDAY is only supported for monthly rules".to_string());
+        }
+        by_day.sort_by_key(|day| day.num_days_from_monday());
+
+        Ok(Self {
+            frequency,
+            interval,
+            by_day,
+            by_month_day,
+        })
+    }
+
+    /// The first occurrence strictly after `date`, treating `date` as part of the series.
+    pub fn next_after(&self, date: NaiveDate) -> NaiveDate {
+        self.next_in_series(date, date)
+    }
+
+    /// Like `next_after`, but monthly rules without `BYMONTHDAY` keep the day of
+    /// month of `series_start`, so a series begun on the 31st returns to the 31st
+    /// after landing on a shorter month's last day.
+    pub fn next_in_series(&self, date: NaiveDate, series_start: NaiveDate) -> NaiveDate {
+        match self.frequency {
+            Frequency::Daily => date + Duration::days(self.interval as i64),
+            Frequency::Weekly if self.by_day.is_empty() => {
+                date + Duration::weeks(self.interval as i64)
+            }
+            Frequency::Weekly => {
+                let week_start =
+                    |d: NaiveDate| d - Duration::days(d.weekday().num_days_from_monday() as i64);
+                let anchor_week = week_start(date);
+                let mut candidate = date + Duration::days(1);
+                loop {
+                    let weeks_apart = (week_start(candidate) - anchor_week).num_weeks();
+                    if weeks_apart % self.interval as i64 == 0
+                        && self.by_day.contains(&candidate.weekday())
+                    {
+                        return candidate;
+                    }
+                    candidate += Duration::days(1);
+                }
+            }
+            Frequency::Monthly => {
+                let day = self.by_month_day.unwrap_or(series_start.day());
+                let mut months = date.year() * 12 + date.month0() as i32;
+                // The next occurrence may still fall in the current month when `date`
+                // is before this month's occurrence
+                if date.day() >= clamp_day(date.year(), date.month(), day) {
+                    months += self.interval as i32;
+                }
+                let (year, month) = (months.div_euclid(12), months.rem_euclid(12) as u32 + 1);
+                NaiveDate::from_ymd_opt(year, month, clamp_day(year, month, day)).unwrap_or(date)
+            }
+        }
+    }
+}
+
+/// Clamp `day` to the last day of the month, so "the 31st" lands on Feb 28/29.
+fn clamp_day(year: i32, month: u32, day: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    let last_day = NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|first| first.pred_opt())
+        .map(|last| last.day())
+        .unwrap_or(28);
+    day.min(last_day)
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+        };
+        write!(f, "FREQ={}", frequency)?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter().map(|day| weekday_code(*day)).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(day) = self.by_month_day {
+            write!(f, ";BYMONTHDAY={}", day)?;
+        }
+        Ok(())
+    }
+}
+
+/// The planned date of the earliest instance in a series that has one.
+fn series_start(conn: &rusqlite::Connection, series_id: &str) -> Result<Option<NaiveDate>, String> {
+    let start: Option<String> = conn
+        .query_row(
+            "SELECT COALESCE(scheduled_for, due_date) FROM tasks
+             WHERE series_id = ?1 AND COALESCE(scheduled_for, due_date) IS NOT NULL
+             ORDER BY created_at LIMIT 1",
+            params![series_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(start.and_then(|start| crate::schedule::parse_date(&start).ok()))
+}
+
+/// Create the next instance of a recurring task that was just completed.
+///
+/// The new task copies text, priority, estimate, tags and checklist (unchecked),
+/// and shifts `scheduled_for` / `due_date` to the next occurrence after today.
+/// Returns `None` when the task does not recur or its next instance already
+/// exists, e.g. after un-completing and completing it again.
+pub fn spawn_next_instance(
+    conn: &rusqlite::Connection,
+    task: &Task,
+) -> Result<Option<Task>, String> {
+    let Some(rule) = task.recurrence_rule.as_deref() else {
+        return Ok(None);
+    };
+    let rule = RecurrenceRule::parse(rule)?;
+    let series_id = task.series_id.clone().unwrap_or_else(|| task.id.clone());
+
+    let already_spawned: Option<String> = conn
+        .query_row(
+            "SELECT id FROM tasks WHERE series_id = ?1 AND created_at > ?2 LIMIT 1",
+            params![series_id, task.created_at],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Database error: {}", e))?;
+    if already_spawned.is_some() {
+        return Ok(None);
+    }
+
+    let parse = |date: &Option<String>| {
+        date.as_deref()
+            .and_then(|d| crate::schedule::parse_date(d).ok())
+    };
+    let scheduled_for = parse(&task.scheduled_for);
+    let due_date = parse(&task.due_date);
+
+    // Recur from the planned date when there is one, otherwise from today
+    let today = crate::schedule::today();
+    let anchor = scheduled_for.or(due_date).unwrap_or(today);
+    let series_start = series_start(conn, &series_id)?.unwrap_or(anchor);
+
+    // Skip the occurrences missed while the task sat overdue
+    let mut next = rule.next_in_series(anchor, series_start);
+    while next <= today {
+        next = rule.next_in_series(next, series_start);
+    }
+    let shift = next - anchor;
+    let format = |date: Option<NaiveDate>| date.map(|d| (d + shift).format("%Y-%m-%d").to_string());
+    let (next_scheduled, next_due) = match (scheduled_for, due_date) {
+        (None, None) => (Some(next.format("%Y-%m-%d").to_string()), None),
+        (scheduled_for, due_date) => (format(scheduled_for), format(due_date)),
+    };
+
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tasks (id, text, completed, created_at, priority, estimated_pomodoros,
+                            actual_pomodoros, due_date, scheduled_for, recurrence_rule, series_id,
+                            project)
+         VALUES (?1, ?2, 0, ?3, ?4, ?5, 0, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            id,
+            task.text,
+            chrono::Utc::now().to_rfc3339(),
+            task.priority,
+            task.estimated_pomodoros,
+            next_due,
+            next_scheduled,
+            rule.to_string(),
+            series_id,
+            task.project
+        ],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    conn.execute(
+        "INSERT INTO task_tags (task_id, tag_id) SELECT ?1, tag_id FROM task_tags WHERE task_id = ?2",
+        params![id, task.id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    for item in &task.checklist {
+        conn.execute(
+            "INSERT INTO task_checklist_items (id, task_id, text, completed, position, created_at)
+             VALUES (?1, ?2, ?3, 0, ?4, ?5)",
+            params![
+                uuid::Uuid::new_v4().to_string(),
+                id,
+                item.text,
+                item.position,
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    }
+
+    database::load_task(conn, &id).map(Some)
+}
+
+/// Make a task recurring, or stop it recurring when `rule` is `None` or empty.
+#[tauri::command]
+pub async fn set_task_recurrence(
+    state: State<'_, DbPool>,
+    task_id: String,
+    rule: Option<String>,
+) -> Result<Task, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let rule = match rule {
+        Some(rule) if !rule.trim().is_empty() => Some(RecurrenceRule::parse(&rule)?.to_string()),
+        _ => None,
+    };
+
+    // The first recurring instance starts the series; later instances inherit its id
+    conn.execute(
+        "UPDATE tasks SET recurrence_rule = ?1,
+                          series_id = CASE WHEN ?1 IS NULL THEN series_id ELSE COALESCE(series_id, id) END
+         WHERE id = ?2",
+        params![rule, task_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    database::load_task(&conn, &task_id)
+}
+
+/// Every instance of a recurring series, oldest first, each with its own sessions.
+#[tauri::command]
+pub async fn get_recurrence_history(
+    state: State<'_, DbPool>,
+    series_id: String,
+) -> Result<Vec<TaskWithStats>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let instances = database::load_tasks_where(
+        &conn,
+        "series_id = ?1",
+        "created_at ASC",
+        params![series_id],
+    )?;
+
+    instances
+        .into_iter()
+        .map(|task| database::task_with_stats(&conn, task, None, None))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn next(rule: &str, from: &str) -> String {
+        RecurrenceRule::parse(rule)
+            .unwrap()
+            .next_after(date(from))
+            .format("%Y-%m-%d")
+            .to_string()
+    }
+
+    #[test]
+    fn parses_shorthands_and_rrules() {
+        assert_eq!(
+            RecurrenceRule::parse("daily").unwrap().to_string(),
+            "FREQ=DAILY"
+        );
+        assert_eq!(
+            RecurrenceRule::parse("weekdays").unwrap().to_string(),
+            "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR"
+        );
+        assert_eq!(
+            RecurrenceRule::parse("RRULE:freq=weekly;interval=2;byday=fr,mo,fr")
+                .unwrap()
+                .to_string(),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR"
+        );
+        assert_eq!(
+            RecurrenceRule::parse("FREQ=MONTHLY;BYMONTHDAY=15")
+                .unwrap()
+                .by_month_day,
+            Some(15)
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_rules() {
+        for rule in [
+            "",
+            "FREQ=YEARLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;BYDAY=MO",
+            "FREQ=WEEKLY;BYMONTHDAY=3",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=WEEKLY;BYDAY=XX",
+            "FREQ=DAILY;COUNT=3",
+            "INTERVAL=2",
+        ] {
+            assert!(RecurrenceRule::parse(rule).is_err(), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn daily_and_weekly() {
+        assert_eq!(next("daily", "2026-10-17"), "2026-10-18");
+        assert_eq!(next("FREQ=DAILY;INTERVAL=3", "2026-10-30"), "2026-11-02");
+        assert_eq!(next("weekly", "2026-10-17"), "2026-10-24");
+        // Friday to Monday
+        assert_eq!(next("weekdays", "2026-10-16"), "2026-10-19");
+        assert_eq!(next("FREQ=WEEKLY;BYDAY=MO,TH", "2026-10-19"), "2026-10-22");
+        // Every other week: Thursday jumps to Monday two weeks on
+        assert_eq!(
+            next("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH", "2026-10-22"),
+            "2026-11-02"
+        );
+    }
+
+    #[test]
+    fn monthly() {
+        assert_eq!(next("monthly", "2026-10-17"), "2026-11-17");
+        assert_eq!(next("FREQ=MONTHLY;INTERVAL=3", "2026-11-05"), "2027-02-05");
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=20", "2026-10-17"),
+            "2026-10-20"
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=20", "2026-10-20"),
+            "2026-11-20"
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=31", "2027-01-31"),
+            "2027-02-28"
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=31", "2028-01-31"),
+            "2028-02-29"
+        );
+    }
+
+    #[test]
+    fn monthly_keeps_the_series_day() {
+        let rule = RecurrenceRule::parse("monthly").unwrap();
+        let start = date("2027-01-31");
+
+        let mut current = start;
+        let mut dates = Vec::new();
+        for _ in 0..3 {
+            current = rule.next_in_series(current, start);
+            dates.push(current.format("%Y-%m-%d").to_string());
+        }
+        assert_eq!(dates, ["2027-02-28", "2027-03-31", "2027-04-30"]);
+    }
+}
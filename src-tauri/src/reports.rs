@@ -0,0 +1,394 @@
+use crate::database::DbPool;
+use chrono::{Datelike, Duration, NaiveDate};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tauri::State;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Inclusive `(first, last)` day of a report period.
+type DateRange = (NaiveDate, NaiveDate);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportPeriod {
+    /// Monday to Sunday
+    Week,
+    /// Calendar month
+    Month,
+    /// `from` to `to`, compared with the same number of days before `from`
+    Custom,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ReportTotals {
+    /// Completed, uninterrupted work sessions
+    pub work_sessions: u32,
+    pub focus_minutes: u32,
+    pub break_minutes: u32,
+    pub interrupted_sessions: u32,
+    pub tasks_completed: u32,
+    /// Days with at least one completed work session
+    pub active_days: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ReportAverages {
+    pub focus_minutes_per_day: f64,
+    pub focus_minutes_per_active_day: f64,
+    pub work_sessions_per_day: f64,
+}
+
+/// Current minus previous period; the percentages are `None` when the previous
+/// period had nothing to compare against.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ReportDeltas {
+    pub work_sessions: i64,
+    pub focus_minutes: i64,
+    pub tasks_completed: i64,
+    pub work_sessions_percent: Option<f64>,
+    pub focus_minutes_percent: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReportGroup {
+    /// Task id, project name or session type; `None` for sessions without one
+    pub key: Option<String>,
+    pub label: String,
+    pub sessions: u32,
+    pub minutes: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReportDay {
+    pub date: String,
+    pub work_sessions: u32,
+    pub focus_minutes: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Report {
+    pub period: ReportPeriod,
+    pub from: String,
+    pub to: String,
+    pub previous_from: String,
+    pub previous_to: String,
+    pub totals: ReportTotals,
+    pub previous: ReportTotals,
+    pub averages: ReportAverages,
+    pub deltas: ReportDeltas,
+    /// Work sessions per task, most focused first
+    pub by_task: Vec<ReportGroup>,
+    /// Work sessions per project, most focused first
+    pub by_project: Vec<ReportGroup>,
+    /// Completed sessions of each type
+    pub by_session_type: Vec<ReportGroup>,
+    /// Every day in the range, including days without sessions
+    pub days: Vec<ReportDay>,
+}
+
+struct SessionRow {
+    session_type: String,
+    duration_minutes: u32,
+    day: String,
+    task_id: Option<String>,
+    text: Option<String>,
+    project: Option<String>,
+    completed: bool,
+    interrupted: bool,
+}
+
+/// `date` moved by `days`, or an error past the supported calendar.
+fn shift(date: NaiveDate, days: i64) -> Result<NaiveDate, String> {
+    date.checked_add_signed(Duration::days(days))
+        .ok_or_else(|| "Report range is outside the supported dates".to_string())
+}
+
+/// The current range and the one it is compared with.
+fn period_bounds(
+    period: ReportPeriod,
+    anchor: NaiveDate,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<(DateRange, DateRange), String> {
+    match period {
+        ReportPeriod::Week => {
+            let start = shift(anchor, -(anchor.weekday().num_days_from_monday() as i64))?;
+            Ok((
+                (start, shift(start, 6)?),
+                (shift(start, -7)?, shift(start, -1)?),
+            ))
+        }
+        ReportPeriod::Month => {
+            let start = anchor.with_day(1).unwrap_or(anchor);
+            let next = if start.month() == 12 {
+                NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+            } else {
+                NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)
+            }
+            .ok_or_else(|| "Report range is outside the supported dates".to_string())?;
+            let previous_end = shift(start, -1)?;
+            let previous_start = previous_end.with_day(1).unwrap_or(previous_end);
+            Ok(((start, shift(next, -1)?), (previous_start, previous_end)))
+        }
+        ReportPeriod::Custom => {
+            let (Some(from), Some(to)) = (from, to) else {
+                return Err("A custom report needs both `from` and `to`".to_string());
+            };
+            if to < from {
+                return Err("`to` must not be before `from`".to_string());
+            }
+            let length = (to - from).num_days() + 1;
+            if length > crate::heatmap::MAX_RANGE_DAYS {
+                return Err(format!(
+                    "Report range is too long; it can cover at most {} days",
+                    crate::heatmap::MAX_RANGE_DAYS
+                ));
+            }
+            Ok(((from, to), (shift(from, -length)?, shift(from, -1)?)))
+        }
+    }
+}
+
+fn load_sessions(
+    conn: &rusqlite::Connection,
+    from: &str,
+    to: &str,
+) -> Result<Vec<SessionRow>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.session_type, s.duration_minutes, DATE(s.started_at, 'localtime'),
+                    s.task_id, t.text, t.project, s.completed_at IS NOT NULL, s.interrupted
+             FROM pomodoro_sessions s
+             LEFT JOIN tasks t ON t.id = s.task_id
+             WHERE DATE(s.started_at, 'localtime') BETWEEN ?1 AND ?2",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let session_iter = stmt
+        .query_map(params![from, to], |row| {
+            Ok(SessionRow {
+                session_type: row.get(0)?,
+                duration_minutes: row.get(1)?,
+                day: row.get(2)?,
+                task_id: row.get(3)?,
+                text: row.get(4)?,
+                project: row.get(5)?,
+                completed: row.get(6)?,
+                interrupted: row.get(7)?,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut sessions = Vec::new();
+    for session in session_iter {
+        sessions.push(session.map_err(|e| format!("Database error: {}", e))?);
+    }
+
+    Ok(sessions)
+}
+
+fn is_focus(session: &SessionRow) -> bool {
+    session.session_type == "work" && session.completed && !session.interrupted
+}
+
+fn totals(
+    conn: &rusqlite::Connection,
+    sessions: &[SessionRow],
+    from: &str,
+    to: &str,
+) -> Result<ReportTotals, String> {
+    let mut totals = ReportTotals::default();
+    let mut active_days: Vec<&str> = Vec::new();
+
+    for session in sessions {
+        if is_focus(session) {
+            totals.work_sessions += 1;
+            totals.focus_minutes += session.duration_minutes;
+            if !active_days.contains(&session.day.as_str()) {
+                active_days.push(&session.day);
+            }
+        } else if session.session_type == "work" && session.interrupted {
+            totals.interrupted_sessions += 1;
+        } else if session.session_type != "work" && session.completed {
+            totals.break_minutes += session.duration_minutes;
+        }
+    }
+    totals.active_days = active_days.len() as u32;
+
+    totals.tasks_completed = conn
+        .query_row(
+            "SELECT COUNT(*) FROM tasks
+             WHERE completed = 1 AND DATE(completed_at, 'localtime') BETWEEN ?1 AND ?2",
+            params![from, to],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(totals)
+}
+
+fn percent_change(current: u32, previous: u32) -> Option<f64> {
+    if previous == 0 {
+        None
+    } else {
+        let change = (current as f64 - previous as f64) / previous as f64 * 100.0;
+        Some((change * 10.0).round() / 10.0)
+    }
+}
+
+/// Sum sessions into groups, most minutes first.
+fn group_by<'a>(
+    sessions: impl Iterator<Item = &'a SessionRow>,
+    key: impl Fn(&SessionRow) -> (Option<String>, String),
+) -> Vec<ReportGroup> {
+    let mut groups: HashMap<Option<String>, ReportGroup> = HashMap::new();
+    for session in sessions {
+        let (key, label) = key(session);
+        let group = groups.entry(key.clone()).or_insert_with(|| ReportGroup {
+            key,
+            label,
+            sessions: 0,
+            minutes: 0,
+        });
+        group.sessions += 1;
+        group.minutes += session.duration_minutes;
+    }
+
+    let mut groups: Vec<ReportGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.minutes.cmp(&a.minutes).then(a.label.cmp(&b.label)));
+    groups
+}
+
+pub fn build_report(
+    conn: &rusqlite::Connection,
+    period: ReportPeriod,
+    anchor: NaiveDate,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Report, String> {
+    let ((start, end), (previous_start, previous_end)) = period_bounds(period, anchor, from, to)?;
+    let format = |date: NaiveDate| date.format(DATE_FORMAT).to_string();
+    let (from, to) = (format(start), format(end));
+    let (previous_from, previous_to) = (format(previous_start), format(previous_end));
+
+    let sessions = load_sessions(conn, &from, &to)?;
+    let current = totals(conn, &sessions, &from, &to)?;
+    let previous_sessions = load_sessions(conn, &previous_from, &previous_to)?;
+    let previous = totals(conn, &previous_sessions, &previous_from, &previous_to)?;
+
+    let day_count = ((end - start).num_days() + 1) as f64;
+    let round = |value: f64| (value * 10.0).round() / 10.0;
+    let averages = ReportAverages {
+        focus_minutes_per_day: round(current.focus_minutes as f64 / day_count),
+        focus_minutes_per_active_day: if current.active_days == 0 {
+            0.0
+        } else {
+            round(current.focus_minutes as f64 / current.active_days as f64)
+        },
+        work_sessions_per_day: round(current.work_sessions as f64 / day_count),
+    };
+
+    let deltas = ReportDeltas {
+        work_sessions: current.work_sessions as i64 - previous.work_sessions as i64,
+        focus_minutes: current.focus_minutes as i64 - previous.focus_minutes as i64,
+        tasks_completed: current.tasks_completed as i64 - previous.tasks_completed as i64,
+        work_sessions_percent: percent_change(current.work_sessions, previous.work_sessions),
+        focus_minutes_percent: percent_change(current.focus_minutes, previous.focus_minutes),
+    };
+
+    let focus = || sessions.iter().filter(|session| is_focus(session));
+    let by_task = group_by(focus(), |session| {
+        (
+            session.task_id.clone(),
+            session
+                .text
+                .clone()
+                .unwrap_or_else(|| "No task".to_string()),
+        )
+    });
+    let by_project = group_by(focus(), |session| {
+        (
+            session.project.clone(),
+            session
+                .project
+                .clone()
+                .unwrap_or_else(|| "No project".to_string()),
+        )
+    });
+    let by_session_type = group_by(
+        sessions
+            .iter()
+            .filter(|session| session.completed && !session.interrupted),
+        |session| {
+            (
+                Some(session.session_type.clone()),
+                session.session_type.clone(),
+            )
+        },
+    );
+
+    let mut days: BTreeMap<String, ReportDay> = BTreeMap::new();
+    let mut day = start;
+    while day <= end {
+        days.insert(
+            format(day),
+            ReportDay {
+                date: format(day),
+                work_sessions: 0,
+                focus_minutes: 0,
+            },
+        );
+        day += Duration::days(1);
+    }
+    for session in focus() {
+        if let Some(day) = days.get_mut(&session.day) {
+            day.work_sessions += 1;
+            day.focus_minutes += session.duration_minutes;
+        }
+    }
+
+    Ok(Report {
+        period,
+        from,
+        to,
+        previous_from,
+        previous_to,
+        totals: current,
+        previous,
+        averages,
+        deltas,
+        by_task,
+        by_project,
+        by_session_type,
+        days: days.into_values().collect(),
+    })
+}
+
+/// Aggregate sessions for a week or month containing `date` (default today), or
+/// for a custom `from`–`to` range, and compare with the period before it.
+#[tauri::command]
+pub async fn get_report(
+    state: State<'_, DbPool>,
+    period: ReportPeriod,
+    date: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Report, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let anchor = match date {
+        Some(date) => crate::schedule::parse_date(&date)?,
+        None => crate::schedule::today(),
+    };
+    let from = from
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?;
+    let to = to
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?;
+
+    build_report(&conn, period, anchor, from, to)
+}
@@ -0,0 +1,116 @@
+use crate::database::{self, DbPool, Task};
+use chrono::{Duration, Local, NaiveDate};
+use rusqlite::params;
+use tauri::State;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Ordering for date-driven views: overdue first, then by deadline, then priority.
+const SCHEDULE_ORDER: &str = "COALESCE(due_date, scheduled_for) IS NULL,
+    COALESCE(due_date, scheduled_for) ASC, priority DESC, created_at DESC";
+
+/// Today's date in local time, as stored in `due_date` / `scheduled_for`.
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Parse a `YYYY-MM-DD` date coming from the frontend.
+pub fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), DATE_FORMAT)
+        .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", value))
+}
+
+/// Normalize an optional date argument, treating blank strings as "no date".
+fn parse_optional_date(value: Option<String>) -> Result<Option<String>, String> {
+    match value {
+        Some(value) if !value.trim().is_empty() => {
+            Ok(Some(parse_date(&value)?.format(DATE_FORMAT).to_string()))
+        }
+        _ => Ok(None),
+    }
+}
+
+pub fn is_overdue(due_date: Option<&str>) -> bool {
+    due_date
+        .and_then(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok())
+        .map(|date| date < today())
+        .unwrap_or(false)
+}
+
+/// Set or clear a task's due and scheduled dates. `None` or an empty string clears a date.
+#[tauri::command]
+pub async fn set_task_dates(
+    state: State<'_, DbPool>,
+    task_id: String,
+    due_date: Option<String>,
+    scheduled_for: Option<String>,
+) -> Result<Task, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let due_date = parse_optional_date(due_date)?;
+    let scheduled_for = parse_optional_date(scheduled_for)?;
+
+    conn.execute(
+        "UPDATE tasks SET due_date = ?1, scheduled_for = ?2 WHERE id = ?3",
+        params![due_date, scheduled_for, task_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    database::load_task(&conn, &task_id)
+}
+
+/// Open tasks that matter today: scheduled for today or earlier, or due today or earlier.
+#[tauri::command]
+pub async fn get_today_tasks(state: State<'_, DbPool>) -> Result<Vec<Task>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let today = today().format(DATE_FORMAT).to_string();
+    database::load_tasks_where(
+        &conn,
+        "completed = 0 AND (scheduled_for <= ?1 OR due_date <= ?1)",
+        SCHEDULE_ORDER,
+        params![today],
+    )
+}
+
+/// Open tasks scheduled or due within the next `days` days (default 7), excluding today.
+#[tauri::command]
+pub async fn get_upcoming_tasks(
+    state: State<'_, DbPool>,
+    days: Option<u32>,
+) -> Result<Vec<Task>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let today = today();
+    let until = today
+        .checked_add_signed(Duration::days(days.unwrap_or(7) as i64))
+        .ok_or_else(|| "Upcoming range ends after the latest supported date".to_string())?;
+    database::load_tasks_where(
+        &conn,
+        "completed = 0
+         AND NOT (COALESCE(scheduled_for <= ?1, 0) OR COALESCE(due_date <= ?1, 0))
+         AND (scheduled_for <= ?2 OR due_date <= ?2)",
+        SCHEDULE_ORDER,
+        params![
+            today.format(DATE_FORMAT).to_string(),
+            until.format(DATE_FORMAT).to_string()
+        ],
+    )
+}
+
+#[tauri::command]
+pub async fn get_overdue_tasks(state: State<'_, DbPool>) -> Result<Vec<Task>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let today = today().format(DATE_FORMAT).to_string();
+    database::load_tasks_where(
+        &conn,
+        "completed = 0 AND due_date < ?1",
+        "due_date ASC, priority DESC, created_at DESC",
+        params![today],
+    )
+}
@@ -0,0 +1,177 @@
+use crate::database::DbPool;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// A task or session reflection matching a search query.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchHit {
+    /// `task` or `reflection`
+    pub kind: String,
+    /// Task id for `task` hits, session id for `reflection` hits
+    pub id: String,
+    pub task_id: Option<String>,
+    /// Task text, or the session's task text for reflections
+    pub title: String,
+    /// Matching excerpt, HTML-escaped, with hits wrapped in `<mark>`
+    pub snippet: String,
+    /// Local `YYYY-MM-DD` the task was created or the session started
+    pub date: String,
+    /// BM25 score; lower is a better match
+    pub rank: f64,
+}
+
+/// Create the FTS5 index over task text, task notes and session reflections, the
+/// triggers that keep it current, and index the rows already in the database.
+pub fn create_search_index(conn: &rusqlite::Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+            kind UNINDEXED,
+            ref_id UNINDEXED,
+            title,
+            body,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS search_tasks_insert AFTER INSERT ON tasks BEGIN
+            INSERT INTO search_index (kind, ref_id, title, body)
+            VALUES ('task', new.id, new.text, COALESCE(new.notes, ''));
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS search_tasks_update AFTER UPDATE OF text, notes ON tasks BEGIN
+            DELETE FROM search_index WHERE kind = 'task' AND ref_id = old.id;
+            INSERT INTO search_index (kind, ref_id, title, body)
+            VALUES ('task', new.id, new.text, COALESCE(new.notes, ''));
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS search_tasks_delete AFTER DELETE ON tasks BEGIN
+            DELETE FROM search_index WHERE kind = 'task' AND ref_id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS search_sessions_insert AFTER INSERT ON pomodoro_sessions
+        WHEN new.reflection IS NOT NULL BEGIN
+            INSERT INTO search_index (kind, ref_id, title, body)
+            VALUES ('reflection', new.id, '', new.reflection);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS search_sessions_update AFTER UPDATE OF reflection ON pomodoro_sessions BEGIN
+            DELETE FROM search_index WHERE kind = 'reflection' AND ref_id = old.id;
+            INSERT INTO search_index (kind, ref_id, title, body)
+            SELECT 'reflection', new.id, '', new.reflection WHERE new.reflection IS NOT NULL;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS search_sessions_delete AFTER DELETE ON pomodoro_sessions BEGIN
+            DELETE FROM search_index WHERE kind = 'reflection' AND ref_id = old.id;
+        END;
+
+        DELETE FROM search_index;
+
+        INSERT INTO search_index (kind, ref_id, title, body)
+        SELECT 'task', id, text, COALESCE(notes, '') FROM tasks;
+
+        INSERT INTO search_index (kind, ref_id, title, body)
+        SELECT 'reflection', id, '', reflection FROM pomodoro_sessions
+        WHERE reflection IS NOT NULL;",
+    )
+    .map_err(|e| format!("Failed to create search index: {}", e))
+}
+
+/// Private-use characters FTS5 wraps matches in, swapped for `<mark>` tags once
+/// the text around them is escaped.
+const MATCH_START: char = '\u{E000}';
+const MATCH_END: char = '\u{E001}';
+
+/// Escape an FTS5 snippet for HTML and turn its match markers into `<mark>` tags.
+pub fn highlight(snippet: &str) -> String {
+    crate::summary::escape_html(snippet)
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
+
+/// Turn free text into an FTS5 query: every word must match, as a prefix, and
+/// FTS5 operators typed by the user are treated as plain text.
+pub fn to_match_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{}\"*", term))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Search task text, task notes and session reflections.
+///
+/// `completed` restricts hits to tasks (or reflections on tasks) in that state;
+/// `from` / `to` are inclusive `YYYY-MM-DD` bounds on the hit's date.
+#[tauri::command]
+pub async fn search(
+    state: State<'_, DbPool>,
+    query: String,
+    completed: Option<bool>,
+    from: Option<String>,
+    to: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<SearchHit>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let Some(match_query) = to_match_query(&query) else {
+        return Ok(Vec::new());
+    };
+    let from = from
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?
+        .map(|date| date.format("%Y-%m-%d").to_string());
+    let to = to
+        .map(|date| crate::schedule::parse_date(&date))
+        .transpose()?
+        .map(|date| date.format("%Y-%m-%d").to_string());
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT hit.kind, hit.ref_id, t.id, COALESCE(t.text, ''),
+                    snippet(search_index, -1, char(57344), char(57345), '…', 16),
+                    DATE(COALESCE(s.started_at, t.created_at), 'localtime') AS hit_date,
+                    bm25(search_index, 0.0, 0.0, 4.0, 1.0) AS score
+             FROM search_index hit
+             LEFT JOIN pomodoro_sessions s ON hit.kind = 'reflection' AND s.id = hit.ref_id
+             LEFT JOIN tasks t ON t.id = CASE hit.kind WHEN 'task' THEN hit.ref_id ELSE s.task_id END
+             WHERE search_index MATCH ?1
+               AND (?2 IS NULL OR t.completed = ?2)
+               AND (?3 IS NULL OR hit_date >= ?3)
+               AND (?4 IS NULL OR hit_date <= ?4)
+             ORDER BY score
+             LIMIT ?5",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let hit_iter = stmt
+        .query_map(
+            params![match_query, completed, from, to, limit.unwrap_or(50)],
+            |row| {
+                Ok(SearchHit {
+                    kind: row.get(0)?,
+                    id: row.get(1)?,
+                    task_id: row.get(2)?,
+                    title: row.get(3)?,
+                    snippet: highlight(&row.get::<_, String>(4)?),
+                    date: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+                    rank: row.get(6)?,
+                })
+            },
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut hits = Vec::new();
+    for hit in hit_iter {
+        hits.push(hit.map_err(|e| format!("Database error: {}", e))?);
+    }
+
+    Ok(hits)
+}
@@ -0,0 +1,323 @@
+use crate::database::{self, AppSettings, DailyStats, DbPool};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Emitter, State};
+
+/// Event emitted once a day, when a completed session first reaches the daily goal.
+pub const GOAL_REACHED_EVENT: &str = "daily-goal-reached";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalUnit {
+    #[default]
+    Pomodoros,
+    Minutes,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DailyGoal {
+    pub unit: GoalUnit,
+    pub target: u32,
+}
+
+impl Default for DailyGoal {
+    fn default() -> Self {
+        Self {
+            unit: GoalUnit::Pomodoros,
+            target: 8,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GoalProgress {
+    pub date: String,
+    pub unit: GoalUnit,
+    pub target: u32,
+    /// Completed work sessions on the day
+    pub pomodoros: u32,
+    pub focus_minutes: u32,
+    /// `pomodoros` or `focus_minutes`, whichever the goal counts
+    pub achieved: u32,
+    pub percent: f64,
+    pub met: bool,
+    /// The day is one of the configured rest days
+    pub rest_day: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StreakSummary {
+    /// Consecutive days meeting the goal up to today. Rest days and today (while
+    /// it is still in progress) don't break it.
+    pub current: u32,
+    pub longest: u32,
+    pub today: GoalProgress,
+}
+
+/// `(pomodoros, focus minutes)` per local day, for days with completed work sessions.
+pub fn focus_by_day(
+    conn: &rusqlite::Connection,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<BTreeMap<NaiveDate, (u32, u32)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DATE(started_at, 'localtime') AS day, COUNT(*), SUM(duration_minutes)
+             FROM pomodoro_sessions
+             WHERE session_type = 'work' AND interrupted = 0 AND completed_at IS NOT NULL
+               AND (?1 IS NULL OR DATE(started_at, 'localtime') >= ?1)
+               AND (?2 IS NULL OR DATE(started_at, 'localtime') <= ?2)
+             GROUP BY day",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![from, to], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, u32>(1)?,
+                row.get::<_, u32>(2)?,
+            ))
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut days = BTreeMap::new();
+    for row in rows {
+        let (day, pomodoros, minutes) = row.map_err(|e| format!("Database error: {}", e))?;
+        if let Ok(day) = crate::schedule::parse_date(&day) {
+            days.insert(day, (pomodoros, minutes));
+        }
+    }
+
+    Ok(days)
+}
+
+pub fn goal_progress(
+    settings: &AppSettings,
+    date: NaiveDate,
+    (pomodoros, focus_minutes): (u32, u32),
+) -> GoalProgress {
+    let goal = &settings.daily_goal;
+    let achieved = match goal.unit {
+        GoalUnit::Pomodoros => pomodoros,
+        GoalUnit::Minutes => focus_minutes,
+    };
+    let target = goal.target.max(1);
+
+    GoalProgress {
+        date: date.format("%Y-%m-%d").to_string(),
+        unit: goal.unit,
+        target,
+        pomodoros,
+        focus_minutes,
+        achieved,
+        percent: (achieved as f64 / target as f64 * 1000.0).round() / 10.0,
+        met: achieved >= target,
+        rest_day: settings.rest_days.contains(&date.weekday()),
+    }
+}
+
+/// Current and longest streak over `days`, evaluated up to `today`.
+pub fn compute_streaks(
+    days: &BTreeMap<NaiveDate, (u32, u32)>,
+    settings: &AppSettings,
+    today: NaiveDate,
+) -> (u32, u32) {
+    let Some(first) = days.keys().next().copied() else {
+        return (0, 0);
+    };
+
+    let (mut current, mut longest) = (0, 0);
+    let mut day = first;
+    while day <= today {
+        let progress = goal_progress(settings, day, days.get(&day).copied().unwrap_or_default());
+        if progress.met {
+            current += 1;
+            longest = longest.max(current);
+        } else if !progress.rest_day && day != today {
+            current = 0;
+        }
+        day += Duration::days(1);
+    }
+
+    (current, longest)
+}
+
+/// Fill in `DailyStats::goal_progress` from the sessions of each row's date.
+pub fn attach_goal_progress(
+    conn: &rusqlite::Connection,
+    settings: &AppSettings,
+    stats: &mut [DailyStats],
+) -> Result<(), String> {
+    let dates: Vec<NaiveDate> = stats
+        .iter()
+        .filter_map(|stat| crate::schedule::parse_date(&stat.date).ok())
+        .collect();
+    let (Some(from), Some(to)) = (dates.iter().min(), dates.iter().max()) else {
+        return Ok(());
+    };
+
+    let days = focus_by_day(
+        conn,
+        Some(&from.format("%Y-%m-%d").to_string()),
+        Some(&to.format("%Y-%m-%d").to_string()),
+    )?;
+    for stat in stats.iter_mut() {
+        if let Ok(date) = crate::schedule::parse_date(&stat.date) {
+            let totals = days.get(&date).copied().unwrap_or_default();
+            stat.goal_progress = Some(goal_progress(settings, date, totals));
+        }
+    }
+
+    Ok(())
+}
+
+fn today_progress(
+    conn: &rusqlite::Connection,
+    settings: &AppSettings,
+) -> Result<GoalProgress, String> {
+    let today = crate::schedule::today();
+    let date = today.format("%Y-%m-%d").to_string();
+    let days = focus_by_day(conn, Some(&date), Some(&date))?;
+
+    Ok(goal_progress(
+        settings,
+        today,
+        days.get(&today).copied().unwrap_or_default(),
+    ))
+}
+
+/// Emit `GOAL_REACHED_EVENT` if the work session that just completed is the one
+/// that reached today's goal.
+pub fn notify_goal_reached(
+    app: &AppHandle,
+    conn: &rusqlite::Connection,
+    session_minutes: u32,
+) -> Result<(), String> {
+    let settings = database::load_settings(app)?;
+    let progress = today_progress(conn, &settings)?;
+
+    let before = progress.achieved.saturating_sub(match progress.unit {
+        GoalUnit::Pomodoros => 1,
+        GoalUnit::Minutes => session_minutes,
+    });
+    if progress.met && before < progress.target {
+        app.emit(GOAL_REACHED_EVENT, progress)
+            .map_err(|e| format!("Failed to emit goal event: {}", e))?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_streaks(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+) -> Result<StreakSummary, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let settings = database::load_settings(&app)?;
+    let days = focus_by_day(&conn, None, None)?;
+    let (current, longest) = compute_streaks(&days, &settings, crate::schedule::today());
+
+    Ok(StreakSummary {
+        current,
+        longest,
+        today: today_progress(&conn, &settings)?,
+    })
+}
+
+pub fn default_rest_days() -> Vec<Weekday> {
+    vec![Weekday::Sat, Weekday::Sun]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn settings(rest_days: Vec<Weekday>) -> AppSettings {
+        AppSettings {
+            daily_goal: DailyGoal {
+                unit: GoalUnit::Pomodoros,
+                target: 2,
+            },
+            rest_days,
+            ..Default::default()
+        }
+    }
+
+    fn days(entries: &[(&str, u32)]) -> BTreeMap<NaiveDate, (u32, u32)> {
+        entries
+            .iter()
+            .map(|&(day, pomodoros)| (date(day), (pomodoros, pomodoros * 25)))
+            .collect()
+    }
+
+    #[test]
+    fn gap_day_resets_the_current_streak() {
+        // 2030-06-03 is a Monday; 06-05 has no sessions at all
+        let days = days(&[("2030-06-03", 2), ("2030-06-04", 3), ("2030-06-06", 2)]);
+        assert_eq!(
+            compute_streaks(&days, &settings(Vec::new()), date("2030-06-06")),
+            (1, 2)
+        );
+    }
+
+    #[test]
+    fn today_not_yet_met_keeps_the_streak() {
+        let days = days(&[("2030-06-03", 2), ("2030-06-04", 2), ("2030-06-05", 1)]);
+        assert_eq!(
+            compute_streaks(&days, &settings(Vec::new()), date("2030-06-05")),
+            (2, 2)
+        );
+        // The same shortfall on a past day ends it
+        assert_eq!(
+            compute_streaks(&days, &settings(Vec::new()), date("2030-06-06")),
+            (0, 2)
+        );
+    }
+
+    #[test]
+    fn longest_streak_outlives_the_current_one() {
+        let days = days(&[
+            ("2030-06-03", 2),
+            ("2030-06-04", 2),
+            ("2030-06-05", 2),
+            ("2030-06-06", 0),
+            ("2030-06-07", 4),
+        ]);
+        assert_eq!(
+            compute_streaks(&days, &settings(Vec::new()), date("2030-06-07")),
+            (1, 3)
+        );
+    }
+
+    #[test]
+    fn rest_days_do_not_break_the_streak() {
+        // Friday, then the weekend off, then Monday
+        let days = days(&[("2030-06-07", 2), ("2030-06-10", 2)]);
+        assert_eq!(
+            compute_streaks(&days, &settings(default_rest_days()), date("2030-06-10")),
+            (2, 2)
+        );
+        assert_eq!(
+            compute_streaks(&days, &settings(Vec::new()), date("2030-06-10")),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn no_sessions_means_no_streak() {
+        assert_eq!(
+            compute_streaks(&BTreeMap::new(), &settings(Vec::new()), date("2030-06-10")),
+            (0, 0)
+        );
+    }
+}
@@ -0,0 +1,459 @@
+use crate::database::{self, AppSettings, DbPool};
+use crate::reports::{self, Report, ReportPeriod};
+use chrono::NaiveDate;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path};
+use tauri::{AppHandle, Manager, State};
+
+const MARKDOWN_TEMPLATE: &str = include_str!("../templates/summary.md");
+const HTML_TEMPLATE: &str = include_str!("../templates/summary.html");
+
+/// Tasks listed under "Top tasks".
+const TOP_TASKS: usize = 5;
+
+/// Extensions a summary may be written to.
+const REPORT_EXTENSIONS: [&str; 5] = ["md", "markdown", "txt", "html", "htm"];
+
+/// Markdown heatmap cells for levels 0–4.
+const HEATMAP_BLOCKS: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryFormat {
+    Markdown,
+    Html,
+}
+
+impl SummaryFormat {
+    /// `.html` / `.htm` files get HTML, anything else Markdown.
+    fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("html") | Some("htm") => SummaryFormat::Html,
+            _ => SummaryFormat::Markdown,
+        }
+    }
+}
+
+/// Which report to summarize; same meaning as the arguments of `get_report`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SummaryRequest {
+    pub period: ReportPeriod,
+    pub date: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Read the template from this file instead of the built-in one. It must be
+    /// in the bundled `templates/` directory or the app config directory;
+    /// relative paths are taken from the latter.
+    pub template_path: Option<String>,
+}
+
+struct SessionNote {
+    date: String,
+    task: Option<String>,
+    reflection: String,
+}
+
+pub fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn format_minutes(minutes: u32) -> String {
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+fn signed(value: i64) -> String {
+    if value > 0 {
+        format!("+{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn title(report: &Report, start: NaiveDate) -> String {
+    match report.period {
+        ReportPeriod::Week => format!("Focus summary: week of {}", report.from),
+        ReportPeriod::Month => format!("Focus summary: {}", start.format("%B %Y")),
+        ReportPeriod::Custom => "Focus summary".to_string(),
+    }
+}
+
+/// Session reflections written during the report period, oldest first.
+fn session_notes(
+    conn: &rusqlite::Connection,
+    from: &str,
+    to: &str,
+) -> Result<Vec<SessionNote>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DATE(s.started_at, 'localtime'), t.text, s.reflection
+             FROM pomodoro_sessions s
+             LEFT JOIN tasks t ON t.id = s.task_id
+             WHERE TRIM(COALESCE(s.reflection, '')) != ''
+               AND DATE(s.started_at, 'localtime') BETWEEN ?1 AND ?2
+             ORDER BY s.started_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let note_iter = stmt
+        .query_map(params![from, to], |row| {
+            Ok(SessionNote {
+                date: row.get(0)?,
+                task: row.get(1)?,
+                reflection: row.get::<_, String>(2)?.trim().to_string(),
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut notes = Vec::new();
+    for note in note_iter {
+        notes.push(note.map_err(|e| format!("Database error: {}", e))?);
+    }
+
+    Ok(notes)
+}
+
+fn top_tasks(report: &Report, format: SummaryFormat) -> String {
+    let tasks = report.by_task.iter().take(TOP_TASKS);
+    if report.by_task.is_empty() {
+        return match format {
+            SummaryFormat::Markdown => "_No focus sessions in this period._".to_string(),
+            SummaryFormat::Html => "<p>No focus sessions in this period.</p>".to_string(),
+        };
+    }
+
+    match format {
+        SummaryFormat::Markdown => tasks
+            .enumerate()
+            .map(|(index, task)| {
+                format!(
+                    "{}. {} — {} ({} pomodoro{})",
+                    index + 1,
+                    task.label.replace('\n', " "),
+                    format_minutes(task.minutes),
+                    task.sessions,
+                    if task.sessions == 1 { "" } else { "s" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        SummaryFormat::Html => format!(
+            "<ol>\n{}\n</ol>",
+            tasks
+                .map(|task| format!(
+                    "  <li>{} — {} ({} pomodoro{})</li>",
+                    escape_html(&task.label),
+                    format_minutes(task.minutes),
+                    task.sessions,
+                    if task.sessions == 1 { "" } else { "s" }
+                ))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    }
+}
+
+/// One cell per day of the report, shaded by focused minutes.
+fn heatmap_strip(report: &Report, format: SummaryFormat) -> String {
+    let minutes: Vec<u32> = report.days.iter().map(|day| day.focus_minutes).collect();
+    let thresholds = crate::heatmap::level_thresholds(&minutes);
+    let levels = report.days.iter().map(|day| {
+        (
+            day,
+            crate::heatmap::level_for(day.focus_minutes, &thresholds),
+        )
+    });
+
+    match format {
+        SummaryFormat::Markdown => {
+            let strip: String = levels
+                .map(|(_, level)| HEATMAP_BLOCKS[level as usize])
+                .collect();
+            format!("`{}`", strip)
+        }
+        SummaryFormat::Html => format!(
+            "<div class=\"heatmap\">\n{}\n</div>",
+            levels
+                .map(|(day, level)| format!(
+                    "  <span class=\"level-{}\" title=\"{}: {}\"></span>",
+                    level,
+                    day.date,
+                    format_minutes(day.focus_minutes)
+                ))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    }
+}
+
+fn notes(notes: &[SessionNote], format: SummaryFormat) -> String {
+    if notes.is_empty() {
+        return match format {
+            SummaryFormat::Markdown => "_No session notes._".to_string(),
+            SummaryFormat::Html => "<p>No session notes.</p>".to_string(),
+        };
+    }
+
+    let line = |note: &SessionNote, escape: &dyn Fn(&str) -> String| match &note.task {
+        Some(task) => format!(
+            "{} · {}: {}",
+            note.date,
+            escape(task),
+            escape(&note.reflection)
+        ),
+        None => format!("{}: {}", note.date, escape(&note.reflection)),
+    };
+
+    match format {
+        SummaryFormat::Markdown => notes
+            .iter()
+            .map(|note| format!("- {}", line(note, &|value| value.replace('\n', " "))))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        SummaryFormat::Html => format!(
+            "<ul>\n{}\n</ul>",
+            notes
+                .iter()
+                .map(|note| format!("  <li>{}</li>", line(note, &escape_html)))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    }
+}
+
+/// Replace every `{{name}}` in `template` with its value. Unknown placeholders
+/// are an error, so typos in custom templates don't end up in the report.
+fn fill_template(template: &str, values: &HashMap<&str, String>) -> Result<String, String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let name = after[..end].trim();
+                let value = values
+                    .get(name)
+                    .ok_or_else(|| format!("Unknown template placeholder: {}", name))?;
+                output.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Read a custom template, refusing files outside the bundled `templates/`
+/// directory and the app config directory.
+fn read_template(app: &AppHandle, path: &str) -> Result<String, String> {
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config directory: {}", e))?;
+    let mut roots = vec![config_dir.clone()];
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        roots.push(resource_dir.join("templates"));
+    }
+
+    let resolved = config_dir
+        .join(path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to read template {}: {}", path, e))?;
+    let allowed = roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(root));
+    if !allowed {
+        return Err(format!(
+            "Templates must be in the app config directory ({}) or the bundled templates",
+            config_dir.display()
+        ));
+    }
+
+    std::fs::read_to_string(&resolved)
+        .map_err(|e| format!("Failed to read template {}: {}", path, e))
+}
+
+/// Reject report destinations that aren't an absolute path to a Markdown, text
+/// or HTML file.
+fn check_output_path(target: &Path) -> Result<(), String> {
+    if !target.is_absolute() || target.components().any(|part| part == Component::ParentDir) {
+        return Err(format!(
+            "Report path must be absolute without '..': {}",
+            target.display()
+        ));
+    }
+
+    let extension = target
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    if !extension.is_some_and(|extension| REPORT_EXTENSIONS.contains(&extension.as_str())) {
+        return Err(format!(
+            "Reports can only be written to .{} files",
+            REPORT_EXTENSIONS.join(", .")
+        ));
+    }
+
+    if target.is_dir() {
+        return Err(format!("Report path is a directory: {}", target.display()));
+    }
+    Ok(())
+}
+
+pub fn render_summary(
+    conn: &rusqlite::Connection,
+    settings: &AppSettings,
+    request: &SummaryRequest,
+    format: SummaryFormat,
+    template: Option<String>,
+) -> Result<String, String> {
+    let today = crate::schedule::today();
+    let anchor = match &request.date {
+        Some(date) => crate::schedule::parse_date(date)?,
+        None => today,
+    };
+    let from = request
+        .from
+        .as_deref()
+        .map(crate::schedule::parse_date)
+        .transpose()?;
+    let to = request
+        .to
+        .as_deref()
+        .map(crate::schedule::parse_date)
+        .transpose()?;
+
+    let report = reports::build_report(conn, request.period, anchor, from, to)?;
+    let start = crate::schedule::parse_date(&report.from)?;
+    let end = crate::schedule::parse_date(&report.to)?;
+
+    let days = crate::streaks::focus_by_day(conn, None, Some(&report.to))?;
+    let (current_streak, longest_streak) =
+        crate::streaks::compute_streaks(&days, settings, end.min(today));
+    let session_notes = session_notes(conn, &report.from, &report.to)?;
+
+    let template = template.unwrap_or_else(|| match format {
+        SummaryFormat::Markdown => MARKDOWN_TEMPLATE.to_string(),
+        SummaryFormat::Html => HTML_TEMPLATE.to_string(),
+    });
+
+    let text = |value: String| match format {
+        SummaryFormat::Markdown => value,
+        SummaryFormat::Html => escape_html(&value),
+    };
+    let focus_delta = match report.deltas.focus_minutes_percent {
+        Some(percent) => format!(
+            "{}m ({}{}%)",
+            signed(report.deltas.focus_minutes),
+            if percent > 0.0 { "+" } else { "" },
+            percent
+        ),
+        None => format!("{}m", signed(report.deltas.focus_minutes)),
+    };
+
+    let values: HashMap<&str, String> = HashMap::from([
+        ("title", text(title(&report, start))),
+        ("from", report.from.clone()),
+        ("to", report.to.clone()),
+        (
+            "generated_at",
+            chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        ),
+        ("focus_time", format_minutes(report.totals.focus_minutes)),
+        ("focus_minutes", report.totals.focus_minutes.to_string()),
+        ("focus_delta", focus_delta),
+        ("work_sessions", report.totals.work_sessions.to_string()),
+        ("sessions_delta", signed(report.deltas.work_sessions)),
+        ("tasks_completed", report.totals.tasks_completed.to_string()),
+        ("tasks_delta", signed(report.deltas.tasks_completed)),
+        ("active_days", report.totals.active_days.to_string()),
+        (
+            "interrupted_sessions",
+            report.totals.interrupted_sessions.to_string(),
+        ),
+        ("current_streak", current_streak.to_string()),
+        ("longest_streak", longest_streak.to_string()),
+        ("top_tasks", top_tasks(&report, format)),
+        ("heatmap", heatmap_strip(&report, format)),
+        ("notes", notes(&session_notes, format)),
+    ]);
+
+    fill_template(&template, &values)
+}
+
+/// Render a summary for previewing or copying.
+#[tauri::command]
+pub async fn render_summary_report(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+    request: SummaryRequest,
+    format: SummaryFormat,
+) -> Result<String, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let settings = database::load_settings(&app)?;
+    let template = request
+        .template_path
+        .as_deref()
+        .map(|path| read_template(&app, path))
+        .transpose()?;
+    render_summary(&conn, &settings, &request, format, template)
+}
+
+/// Render a summary and write it to `path`, which must be an absolute path to a
+/// `.md`, `.markdown`, `.txt`, `.html` or `.htm` file. Without `format` it
+/// follows the file extension. Returns the path written.
+#[tauri::command]
+pub async fn write_summary_report(
+    app: AppHandle,
+    state: State<'_, DbPool>,
+    request: SummaryRequest,
+    path: String,
+    format: Option<SummaryFormat>,
+) -> Result<String, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let target = Path::new(&path);
+    check_output_path(target)?;
+    let format = format.unwrap_or_else(|| SummaryFormat::from_path(target));
+    let settings = database::load_settings(&app)?;
+    let template = request
+        .template_path
+        .as_deref()
+        .map(|path| read_template(&app, path))
+        .transpose()?;
+    let content = render_summary(&conn, &settings, &request, format, template)?;
+
+    if let Some(parent) = target
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create report directory: {}", e))?;
+    }
+    std::fs::write(target, content).map_err(|e| format!("Failed to write report: {}", e))?;
+
+    Ok(path)
+}
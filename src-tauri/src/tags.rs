@@ -0,0 +1,492 @@
+use crate::database::{DbPool, PomodoroSession, Task};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub task_count: u32,
+    pub session_count: u32,
+}
+
+/// Normalize user input such as `#Writing ` into the stored tag name (`Writing`).
+///
+/// Names are compared case-insensitively by the `tags` table, so casing is kept
+/// as first entered.
+pub fn normalize_tag_name(name: &str) -> Result<String, String> {
+    let name = name.trim().trim_start_matches('#').trim();
+    if name.is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// Turn an optional tag filter from the frontend into normalized names, dropping blanks.
+pub fn normalize_tag_filter(tags: Option<Vec<String>>) -> Vec<String> {
+    tags.unwrap_or_default()
+        .iter()
+        .filter_map(|tag| normalize_tag_name(tag).ok())
+        .collect()
+}
+
+/// SQL condition requiring the task referenced by `task_column` to carry every
+/// filtered tag. Tag names are bound to `?{first_param}` onwards.
+pub fn task_tag_condition(task_column: &str, first_param: usize, count: usize) -> String {
+    if count == 0 {
+        return "1".to_string();
+    }
+
+    (first_param..first_param + count)
+        .map(|param| {
+            format!(
+                "EXISTS (SELECT 1 FROM task_tags tt JOIN tags t ON t.id = tt.tag_id
+                         WHERE tt.task_id = {} AND t.name = ?{})",
+                task_column, param
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// SQL condition requiring the session aliased as `session_alias` to carry every
+/// filtered tag, either directly or through its task. Tag names are bound to
+/// `?{first_param}` onwards.
+pub fn session_tag_condition(session_alias: &str, first_param: usize, count: usize) -> String {
+    if count == 0 {
+        return "1".to_string();
+    }
+
+    (first_param..first_param + count)
+        .map(|param| {
+            format!(
+                "(EXISTS (SELECT 1 FROM session_tags st JOIN tags t ON t.id = st.tag_id
+                          WHERE st.session_id = {alias}.id AND t.name = ?{param})
+                  OR EXISTS (SELECT 1 FROM task_tags tt JOIN tags t ON t.id = tt.tag_id
+                             WHERE tt.task_id = {alias}.task_id AND t.name = ?{param}))",
+                alias = session_alias,
+                param = param
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// Look up a tag by name, creating it if needed, and return its id.
+pub fn ensure_tag(conn: &rusqlite::Connection, name: &str) -> Result<String, String> {
+    let name = normalize_tag_name(name)?;
+
+    if let Some(id) = find_tag_id(conn, &name)? {
+        return Ok(id);
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tags (id, name, created_at) VALUES (?1, ?2, ?3)",
+        params![id, name, chrono::Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(id)
+}
+
+fn find_tag_id(conn: &rusqlite::Connection, name: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT id FROM tags WHERE name = ?1",
+        params![name],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| format!("Database error: {}", e))
+}
+
+fn tag_by_id(conn: &rusqlite::Connection, tag_id: &str) -> Result<Tag, String> {
+    conn.query_row(
+        "SELECT id, name,
+                (SELECT COUNT(*) FROM task_tags WHERE tag_id = tags.id),
+                (SELECT COUNT(*) FROM session_tags WHERE tag_id = tags.id)
+         FROM tags WHERE id = ?1",
+        params![tag_id],
+        |row| {
+            Ok(Tag {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                task_count: row.get(2)?,
+                session_count: row.get(3)?,
+            })
+        },
+    )
+    .map_err(|e| format!("Database error: {}", e))
+}
+
+fn task_tag_names(conn: &rusqlite::Connection, task_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT t.name FROM task_tags tt JOIN tags t ON t.id = tt.tag_id
+             WHERE tt.task_id = ?1 ORDER BY t.name COLLATE NOCASE",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let names = stmt
+        .query_map(params![task_id], |row| row.get(0))
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(names)
+}
+
+fn session_tag_names(conn: &rusqlite::Connection, session_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT t.name FROM session_tags st JOIN tags t ON t.id = st.tag_id
+             WHERE st.session_id = ?1 ORDER BY t.name COLLATE NOCASE",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let names = stmt
+        .query_map(params![session_id], |row| row.get(0))
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(names)
+}
+
+/// Fill in `Task::tags` for tasks loaded from the database.
+pub fn attach_task_tags(conn: &rusqlite::Connection, tasks: &mut [Task]) -> Result<(), String> {
+    for task in tasks.iter_mut() {
+        task.tags = task_tag_names(conn, &task.id)?;
+    }
+    Ok(())
+}
+
+/// Fill in `PomodoroSession::tags` with the tags set directly on each session.
+pub fn attach_session_tags(
+    conn: &rusqlite::Connection,
+    sessions: &mut [PomodoroSession],
+) -> Result<(), String> {
+    for session in sessions.iter_mut() {
+        session.tags = session_tag_names(conn, &session.id)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_tags(state: State<'_, DbPool>) -> Result<Vec<Tag>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name,
+                    (SELECT COUNT(*) FROM task_tags WHERE tag_id = tags.id),
+                    (SELECT COUNT(*) FROM session_tags WHERE tag_id = tags.id)
+             FROM tags ORDER BY name COLLATE NOCASE",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let tag_iter = stmt
+        .query_map([], |row| {
+            Ok(Tag {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                task_count: row.get(2)?,
+                session_count: row.get(3)?,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut tags = Vec::new();
+    for tag in tag_iter {
+        tags.push(tag.map_err(|e| format!("Database error: {}", e))?);
+    }
+
+    Ok(tags)
+}
+
+#[tauri::command]
+pub async fn tag_task(
+    state: State<'_, DbPool>,
+    task_id: String,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    conn.query_row(
+        "SELECT 1 FROM tasks WHERE id = ?1",
+        params![task_id],
+        |_| Ok(()),
+    )
+    .optional()
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+    let tag_id = ensure_tag(&conn, &tag)?;
+    conn.execute(
+        "INSERT OR IGNORE INTO task_tags (task_id, tag_id) VALUES (?1, ?2)",
+        params![task_id, tag_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    task_tag_names(&conn, &task_id)
+}
+
+#[tauri::command]
+pub async fn untag_task(
+    state: State<'_, DbPool>,
+    task_id: String,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let name = normalize_tag_name(&tag)?;
+    conn.execute(
+        "DELETE FROM task_tags
+         WHERE task_id = ?1 AND tag_id = (SELECT id FROM tags WHERE name = ?2)",
+        params![task_id, name],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    task_tag_names(&conn, &task_id)
+}
+
+/// Tag a session directly, typically one started without a task.
+#[tauri::command]
+pub async fn tag_session(
+    state: State<'_, DbPool>,
+    session_id: String,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    conn.query_row(
+        "SELECT 1 FROM pomodoro_sessions WHERE id = ?1",
+        params![session_id],
+        |_| Ok(()),
+    )
+    .optional()
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+    let tag_id = ensure_tag(&conn, &tag)?;
+    conn.execute(
+        "INSERT OR IGNORE INTO session_tags (session_id, tag_id) VALUES (?1, ?2)",
+        params![session_id, tag_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    session_tag_names(&conn, &session_id)
+}
+
+#[tauri::command]
+pub async fn untag_session(
+    state: State<'_, DbPool>,
+    session_id: String,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let name = normalize_tag_name(&tag)?;
+    conn.execute(
+        "DELETE FROM session_tags
+         WHERE session_id = ?1 AND tag_id = (SELECT id FROM tags WHERE name = ?2)",
+        params![session_id, name],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    session_tag_names(&conn, &session_id)
+}
+
+#[tauri::command]
+pub async fn rename_tag(
+    state: State<'_, DbPool>,
+    tag: String,
+    new_name: String,
+) -> Result<Tag, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    let name = normalize_tag_name(&tag)?;
+    let new_name = normalize_tag_name(&new_name)?;
+
+    let tag_id = find_tag_id(&conn, &name)?.ok_or_else(|| format!("Tag not found: {}", name))?;
+    if let Some(existing_id) = find_tag_id(&conn, &new_name)? {
+        // Only a change of casing is allowed to hit the same row
+        if existing_id != tag_id {
+            return Err(format!(
+                "Tag '{}' already exists; merge the tags instead",
+                new_name
+            ));
+        }
+    }
+
+    conn.execute(
+        "UPDATE tags SET name = ?1 WHERE id = ?2",
+        params![new_name, tag_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    tag_by_id(&conn, &tag_id)
+}
+
+/// Reassign `source`'s tasks and sessions to `target`, creating it if needed,
+/// and delete `source` in one transaction.
+fn merge_tag_rows(
+    conn: &mut rusqlite::Connection,
+    source: &str,
+    target: &str,
+) -> Result<Tag, String> {
+    let source = normalize_tag_name(source)?;
+    let source_id =
+        find_tag_id(conn, &source)?.ok_or_else(|| format!("Tag not found: {}", source))?;
+    let target_id = ensure_tag(conn, target)?;
+
+    if source_id == target_id {
+        return tag_by_id(conn, &target_id);
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    tx.execute(
+        "INSERT OR IGNORE INTO task_tags (task_id, tag_id)
+         SELECT task_id, ?2 FROM task_tags WHERE tag_id = ?1",
+        params![source_id, target_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    tx.execute(
+        "INSERT OR IGNORE INTO session_tags (session_id, tag_id)
+         SELECT session_id, ?2 FROM session_tags WHERE tag_id = ?1",
+        params![source_id, target_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    tx.execute("DELETE FROM task_tags WHERE tag_id = ?1", params![source_id])
+        .map_err(|e| format!("Database error: {}", e))?;
+    tx.execute("DELETE FROM session_tags WHERE tag_id = ?1", params![source_id])
+        .map_err(|e| format!("Database error: {}", e))?;
+    tx.execute("DELETE FROM tags WHERE id = ?1", params![source_id])
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    tag_by_id(conn, &target_id)
+}
+
+/// Move every task and session from `source` onto `target`, then drop `source`.
+#[tauri::command]
+pub async fn merge_tags(
+    state: State<'_, DbPool>,
+    source: String,
+    target: String,
+) -> Result<Tag, String> {
+    let pool = state.inner();
+    let mut conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    merge_tag_rows(&mut conn, &source, &target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag_db() -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE tags (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                created_at TEXT NOT NULL
+            );
+            CREATE TABLE task_tags (
+                task_id TEXT NOT NULL,
+                tag_id TEXT NOT NULL,
+                PRIMARY KEY(task_id, tag_id)
+            );
+            CREATE TABLE session_tags (
+                session_id TEXT NOT NULL,
+                tag_id TEXT NOT NULL,
+                PRIMARY KEY(session_id, tag_id)
+            );",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn tag(conn: &rusqlite::Connection, table: &str, owner_id: &str, name: &str) {
+        let tag_id = ensure_tag(conn, name).unwrap();
+        conn.execute(
+            &format!("INSERT OR IGNORE INTO {} VALUES (?1, ?2)", table),
+            params![owner_id, tag_id],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn tag_filter_is_normalized_and_blanks_dropped() {
+        assert!(normalize_tag_filter(None).is_empty());
+        assert_eq!(
+            normalize_tag_filter(Some(vec![
+                " #Writing ".to_string(),
+                "".to_string(),
+                "#".to_string(),
+                "deep work".to_string(),
+            ])),
+            vec!["Writing".to_string(), "deep work".to_string()]
+        );
+    }
+
+    #[test]
+    fn merge_moves_tasks_and_sessions_without_duplicates() {
+        let mut conn = tag_db();
+        tag(&conn, "task_tags", "t1", "writing");
+        tag(&conn, "task_tags", "t2", "writing");
+        tag(&conn, "task_tags", "t2", "Blog");
+        tag(&conn, "session_tags", "s1", "writing");
+
+        let merged = merge_tag_rows(&mut conn, "#Writing", "blog").unwrap();
+        assert_eq!(merged.name, "Blog");
+        assert_eq!((merged.task_count, merged.session_count), (2, 1));
+        assert_eq!(find_tag_id(&conn, "writing").unwrap(), None);
+        assert_eq!(task_tag_names(&conn, "t2").unwrap(), vec!["Blog"]);
+    }
+
+    #[test]
+    fn merge_creates_a_missing_target() {
+        let mut conn = tag_db();
+        tag(&conn, "task_tags", "t1", "draft");
+
+        let merged = merge_tag_rows(&mut conn, "draft", "Drafts").unwrap();
+        assert_eq!(merged.name, "Drafts");
+        assert_eq!(merged.task_count, 1);
+    }
+
+    #[test]
+    fn merge_into_itself_changes_nothing() {
+        let mut conn = tag_db();
+        tag(&conn, "task_tags", "t1", "Focus");
+
+        let merged = merge_tag_rows(&mut conn, "focus", "FOCUS").unwrap();
+        assert_eq!(merged.name, "Focus");
+        assert_eq!(merged.task_count, 1);
+    }
+
+    #[test]
+    fn merge_rejects_an_unknown_source() {
+        let mut conn = tag_db();
+        assert_eq!(
+            merge_tag_rows(&mut conn, "missing", "Focus").unwrap_err(),
+            "Tag not found: missing"
+        );
+        assert_eq!(find_tag_id(&conn, "Focus").unwrap(), None);
+    }
+}
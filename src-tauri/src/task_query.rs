@@ -0,0 +1,297 @@
+use crate::database::{self, DbPool, Task};
+use rusqlite::params;
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 500;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSort {
+    /// Highest priority first, then newest; the order `get_tasks` uses
+    #[default]
+    Priority,
+    Newest,
+    Oldest,
+    /// Earliest due date first, tasks without one last
+    DueDate,
+    /// Alphabetical by text
+    Text,
+    /// Most recently completed first
+    CompletedAt,
+}
+
+impl TaskSort {
+    /// Sort key expressions, all compared in the same direction, ending in `id`
+    /// so every row has a unique position for keyset pagination.
+    fn keys(self) -> (&'static [&'static str], &'static str) {
+        match self {
+            TaskSort::Priority => (&["COALESCE(priority, 0)", "created_at", "id"], "DESC"),
+            TaskSort::Newest => (&["created_at", "id"], "DESC"),
+            TaskSort::Oldest => (&["created_at", "id"], "ASC"),
+            TaskSort::DueDate => (
+                &["COALESCE(due_date, '9999-12-31')", "created_at", "id"],
+                "ASC",
+            ),
+            TaskSort::Text => (&["LOWER(text)", "id"], "ASC"),
+            TaskSort::CompletedAt => (&["COALESCE(completed_at, '')", "id"], "DESC"),
+        }
+    }
+
+    /// The values of `keys()` for a loaded task, used to build the next cursor.
+    fn key_values(self, task: &Task) -> Vec<Value> {
+        let text = |value: &str| Value::Text(value.to_string());
+        match self {
+            TaskSort::Priority => vec![
+                Value::Integer(task.priority as i64),
+                text(&task.created_at),
+                text(&task.id),
+            ],
+            TaskSort::Newest | TaskSort::Oldest => vec![text(&task.created_at), text(&task.id)],
+            TaskSort::DueDate => vec![
+                text(task.due_date.as_deref().unwrap_or("9999-12-31")),
+                text(&task.created_at),
+                text(&task.id),
+            ],
+            // SQLite's LOWER() only folds ASCII
+            TaskSort::Text => vec![text(&task.text.to_ascii_lowercase()), text(&task.id)],
+            TaskSort::CompletedAt => vec![
+                text(task.completed_at.as_deref().unwrap_or("")),
+                text(&task.id),
+            ],
+        }
+    }
+}
+
+/// Filters, sort order and page position for `query_tasks`. Every field is optional.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TaskQuery {
+    pub completed: Option<bool>,
+    /// Inclusive `YYYY-MM-DD` bounds on the local creation date
+    pub created_from: Option<String>,
+    pub created_to: Option<String>,
+    pub priority: Option<i32>,
+    pub project: Option<String>,
+    /// Matched against task text and notes
+    pub search: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// Archived tasks are left out unless this is set
+    pub include_archived: bool,
+    pub sort: TaskSort,
+    pub limit: Option<u32>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskPage {
+    pub tasks: Vec<Task>,
+    /// Pass back as `cursor` to fetch the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Serialize sort key values into an opaque cursor string.
+pub fn encode_cursor(values: &[Value]) -> String {
+    let json: Vec<serde_json::Value> = values
+        .iter()
+        .map(|value| match value {
+            Value::Integer(n) => serde_json::Value::from(*n),
+            Value::Real(n) => serde_json::Value::from(*n),
+            Value::Text(s) => serde_json::Value::from(s.as_str()),
+            _ => serde_json::Value::Null,
+        })
+        .collect();
+    serde_json::Value::Array(json).to_string()
+}
+
+pub fn decode_cursor(cursor: &str) -> Result<Vec<Value>, String> {
+    let json: Vec<serde_json::Value> =
+        serde_json::from_str(cursor).map_err(|_| "Invalid cursor".to_string())?;
+
+    json.into_iter()
+        .map(|value| match value {
+            serde_json::Value::Number(n) => n
+                .as_i64()
+                .map(Value::Integer)
+                .or_else(|| n.as_f64().map(Value::Real))
+                .ok_or_else(|| "Invalid cursor".to_string()),
+            serde_json::Value::String(s) => Ok(Value::Text(s)),
+            _ => Err("Invalid cursor".to_string()),
+        })
+        .collect()
+}
+
+/// Load one page of tasks matching `query`.
+pub fn query_task_page(conn: &rusqlite::Connection, query: TaskQuery) -> Result<TaskPage, String> {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    fn bind(values: &mut Vec<Value>, value: Value) -> String {
+        values.push(value);
+        format!("?{}", values.len())
+    }
+
+    if !query.include_archived {
+        conditions.push("archived_at IS NULL".to_string());
+    }
+    if let Some(completed) = query.completed {
+        let param = bind(&mut values, Value::Integer(completed as i64));
+        conditions.push(format!("completed = {}", param));
+    }
+    if let Some(priority) = query.priority {
+        let param = bind(&mut values, Value::Integer(priority as i64));
+        conditions.push(format!("COALESCE(priority, 0) = {}", param));
+    }
+    if let Some(project) = query.project {
+        let param = bind(&mut values, Value::Text(project));
+        conditions.push(format!("project = {} COLLATE NOCASE", param));
+    }
+    if let Some(from) = query.created_from.as_deref() {
+        let from = crate::schedule::parse_date(from)?;
+        let param = bind(
+            &mut values,
+            Value::Text(from.format("%Y-%m-%d").to_string()),
+        );
+        conditions.push(format!("DATE(created_at, 'localtime') >= {}", param));
+    }
+    if let Some(to) = query.created_to.as_deref() {
+        let to = crate::schedule::parse_date(to)?;
+        let param = bind(&mut values, Value::Text(to.format("%Y-%m-%d").to_string()));
+        conditions.push(format!("DATE(created_at, 'localtime') <= {}", param));
+    }
+    if let Some(search) = query
+        .search
+        .as_deref()
+        .and_then(crate::search::to_match_query)
+    {
+        let param = bind(&mut values, Value::Text(search));
+        conditions.push(format!(
+            "id IN (SELECT ref_id FROM search_index WHERE kind = 'task' AND search_index MATCH {})",
+            param
+        ));
+    }
+
+    let tags = crate::tags::normalize_tag_filter(query.tags);
+    if !tags.is_empty() {
+        conditions.push(crate::tags::task_tag_condition(
+            "tasks.id",
+            values.len() + 1,
+            tags.len(),
+        ));
+        values.extend(tags.into_iter().map(Value::Text));
+    }
+
+    let (keys, direction) = query.sort.keys();
+    if let Some(cursor) = query.cursor.as_deref() {
+        let cursor_values = decode_cursor(cursor)?;
+        if cursor_values.len() != keys.len() {
+            return Err("Cursor does not match the requested sort order".to_string());
+        }
+        let params: Vec<String> = cursor_values
+            .into_iter()
+            .map(|value| bind(&mut values, value))
+            .collect();
+        conditions.push(format!(
+            "({}) {} ({})",
+            keys.join(", "),
+            if direction == "DESC" { "<" } else { ">" },
+            params.join(", ")
+        ));
+    }
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let condition = if conditions.is_empty() {
+        "1".to_string()
+    } else {
+        conditions.join(" AND ")
+    };
+    let order_by = format!(
+        "{} LIMIT {}",
+        keys.iter()
+            .map(|key| format!("{} {}", key, direction))
+            .collect::<Vec<_>>()
+            .join(", "),
+        // One extra row tells us whether there is another page
+        limit + 1
+    );
+
+    let mut tasks = database::load_tasks_where(
+        conn,
+        &condition,
+        &order_by,
+        rusqlite::params_from_iter(values.iter()),
+    )?;
+
+    let mut next_cursor = None;
+    if tasks.len() > limit as usize {
+        tasks.truncate(limit as usize);
+        next_cursor = tasks
+            .last()
+            .map(|last| encode_cursor(&query.sort.key_values(last)));
+    }
+
+    Ok(TaskPage { tasks, next_cursor })
+}
+
+/// Longest `archive_completed_after_days` the settings accept, about a century.
+pub const MAX_ARCHIVE_AFTER_DAYS: u32 = 36_500;
+
+/// Move completed tasks finished more than `days` days ago into the archive.
+/// Returns how many tasks were archived.
+pub fn archive_completed_tasks_older_than(
+    conn: &rusqlite::Connection,
+    days: u32,
+) -> Result<usize, String> {
+    let cutoff = chrono::Duration::try_days(days as i64)
+        .and_then(|age| chrono::Utc::now().checked_sub_signed(age))
+        .ok_or_else(|| format!("Archive age of {} days is out of range", days))?;
+
+    conn.execute(
+        "UPDATE tasks SET archived_at = ?1
+         WHERE completed = 1 AND archived_at IS NULL
+           AND completed_at IS NOT NULL AND completed_at < ?2",
+        params![chrono::Utc::now().to_rfc3339(), cutoff.to_rfc3339()],
+    )
+    .map_err(|e| format!("Database error: {}", e))
+}
+
+#[tauri::command]
+pub async fn query_tasks(
+    state: State<'_, DbPool>,
+    query: Option<TaskQuery>,
+) -> Result<TaskPage, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    query_task_page(&conn, query.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn archive_completed_tasks(
+    state: State<'_, DbPool>,
+    older_than_days: u32,
+) -> Result<usize, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    archive_completed_tasks_older_than(&conn, older_than_days)
+}
+
+#[tauri::command]
+pub async fn unarchive_task(state: State<'_, DbPool>, task_id: String) -> Result<Task, String> {
+    let pool = state.inner();
+    let conn = pool.get().map_err(|e| format!("Failed to get connection: {}", e))?;
+
+    conn.execute(
+        "UPDATE tasks SET archived_at = NULL WHERE id = ?1",
+        params![task_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    database::load_task(&conn, &task_id)
+}